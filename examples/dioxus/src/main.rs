@@ -86,8 +86,8 @@ fn LoginPage() -> Element {
         spawn(async move {
             let mut wallet_info = phantom_wallet_adapter();
             match wallet_info.sign_message(&input_msg()).await {
-                Ok(tx) => {
-                    signature.set(tx.to_string());
+                Ok(output) => {
+                    signature.set(output.signature.to_string());
                     confirmed.set(true);
                 }
                 Err(err) => {
@@ -127,8 +127,8 @@ fn LoginPage() -> Element {
         spawn(async move {
             let mut wallet_info = solflare_wallet_adapter();
             match wallet_info.sign_message(&input_msg()).await {
-                Ok(tx) => {
-                    signature.set(tx.to_string());
+                Ok(output) => {
+                    signature.set(output.signature.to_string());
                     confirmed.set(true);
                 }
                 Err(err) => {
@@ -168,8 +168,8 @@ fn LoginPage() -> Element {
         spawn(async move {
             let mut wallet_info = backpack_wallet_adapter();
             match wallet_info.sign_message(&input_msg()).await {
-                Ok(tx) => {
-                    signature.set(tx.to_string());
+                Ok(output) => {
+                    signature.set(output.signature.to_string());
                     confirmed.set(true);
                 }
                 Err(err) => {
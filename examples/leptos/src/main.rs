@@ -93,8 +93,8 @@ pub fn LoginPage() -> impl IntoView {
                 .value();
 
             match wallet_info.sign_message(&input_msg).await {
-                Ok(tx) => {
-                    set_sig.set(tx.to_string());
+                Ok(output) => {
+                    set_sig.set(output.signature.to_string());
                     set_confirmed.set(true);
                 }
                 Err(err) => {
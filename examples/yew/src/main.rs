@@ -169,8 +169,8 @@ pub fn LoginPage() -> Html {
                 let mut wallet_info = (*phantom_wallet_adapter).clone();
 
                 match wallet_info.sign_message(&input_msg).await {
-                    Ok(tx) => {
-                        signature.set(tx.to_string());
+                    Ok(output) => {
+                        signature.set(output.signature.to_string());
                         confirmed.set(true);
                     }
                     Err(err) => {
@@ -243,8 +243,8 @@ pub fn LoginPage() -> Html {
                 let mut wallet_info = (*solflare_wallet_adapter).clone();
 
                 match wallet_info.sign_message(&input_msg).await {
-                    Ok(tx) => {
-                        signature.set(tx.to_string());
+                    Ok(output) => {
+                        signature.set(output.signature.to_string());
                         confirmed.set(true);
                     }
                     Err(err) => {
@@ -317,8 +317,8 @@ pub fn LoginPage() -> Html {
                 let mut wallet_info = (*backpack_wallet_adapter).clone();
 
                 match wallet_info.sign_message(&input_msg).await {
-                    Ok(tx) => {
-                        signature.set(tx.to_string());
+                    Ok(output) => {
+                        signature.set(output.signature.to_string());
                         confirmed.set(true);
                     }
                     Err(err) => {
@@ -1,3 +1,6 @@
+#![allow(deprecated)]
+
 pub mod backpack;
 pub mod phantom;
 pub mod solflare;
+pub mod standard;
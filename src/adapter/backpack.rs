@@ -3,31 +3,25 @@ use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
 extern "C" {
-    #[wasm_bindgen(js_namespace = xnft, js_name = solana)]
-    pub static BACKPACK: Backpack;
-
-    #[wasm_bindgen(js_namespace = window, js_name = xnft)]
-    pub static XNFT: Backpack;
-
     #[wasm_bindgen(extends = Object)]
     pub type Backpack;
 
-    #[wasm_bindgen(method)]
-    pub fn connect(this: &Backpack, options: &JsValue) -> Promise;
+    #[wasm_bindgen(method, catch)]
+    pub fn connect(this: &Backpack, options: &JsValue) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method)]
-    pub fn disconnect(this: &Backpack) -> Promise;
+    #[wasm_bindgen(method, catch)]
+    pub fn disconnect(this: &Backpack) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method, js_name = openXnft)]
-    pub fn open_xnft(this: &Backpack, xnft: &JsValue) -> Promise;
+    #[wasm_bindgen(method, js_name = openXnft, catch)]
+    pub fn open_xnft(this: &Backpack, xnft: &JsValue) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method, js_name = _backpackGetAccounts)]
-    pub fn backpack_get_accounts(this: &Backpack) -> Promise;
+    #[wasm_bindgen(method, js_name = _backpackGetAccounts, catch)]
+    pub fn backpack_get_accounts(this: &Backpack) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method, js_name = signIn)]
-    pub fn sign_in(this: &Backpack, options: &JsValue) -> Promise;
+    #[wasm_bindgen(method, js_name = signIn, catch)]
+    pub fn sign_in(this: &Backpack, options: &JsValue) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method, js_name = sendAndConfirm)]
+    #[wasm_bindgen(method, js_name = sendAndConfirm, catch)]
     pub fn send_and_confirm(
         this: &Backpack,
         tx: &JsValue,
@@ -35,66 +29,80 @@ extern "C" {
         options: &JsValue,
         custom_connection: &JsValue,
         uuid: &JsValue,
-    ) -> Promise;
+    ) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method, js_name = signAndSendTransaction)]
-    pub fn sign_and_send_transaction(this: &Backpack, tx: &JsValue, options: &JsValue) -> Promise;
+    #[wasm_bindgen(method, js_name = signAndSendTransaction, catch)]
+    pub fn sign_and_send_transaction(
+        this: &Backpack,
+        tx: &JsValue,
+        options: &JsValue,
+    ) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method)]
+    #[wasm_bindgen(method, catch)]
     pub fn send(
         this: &Backpack,
         tx: &JsValue,
         signers: &JsValue,
         options: &JsValue,
         custom_connection: &JsValue,
-    ) -> Promise;
+    ) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method, js_name = sendAll)]
+    #[wasm_bindgen(method, js_name = sendAll, catch)]
     pub fn send_all(
         this: &Backpack,
         txs: &JsValue,
         signers: &JsValue,
         options: &JsValue,
         custom_connection: &JsValue,
-    ) -> Promise;
+    ) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method)]
+    #[wasm_bindgen(method, catch)]
     pub fn simulate(
         this: &Backpack,
         tx: &JsValue,
         signers: &JsValue,
         commitment: &JsValue,
         custom_connection: &JsValue,
-    ) -> Promise;
+    ) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method, js_name = signTransaction)]
+    #[wasm_bindgen(method, js_name = signTransaction, catch)]
     pub fn sign_transaction(
         this: &Backpack,
         tx: &JsValue,
         public_key: &JsValue,
         custom_connection: &JsValue,
         uuid: &JsValue,
-    ) -> Promise;
+    ) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method, js_name = signAllTransactions)]
+    #[wasm_bindgen(method, js_name = signAllTransactions, catch)]
     pub fn sign_all_transactions(
         this: &Backpack,
         txs: &JsValue,
         public_key: &JsValue,
         custom_connection: &JsValue,
         uuid: &JsValue,
-    ) -> Promise;
+    ) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method, js_name = prepareSolanaOffchainMessage)]
-    pub fn prepare_solana_offchain_message(this: &Backpack, message: &JsValue) -> Promise;
+    #[wasm_bindgen(method, js_name = prepareSolanaOffchainMessage, catch)]
+    pub fn prepare_solana_offchain_message(
+        this: &Backpack,
+        message: &JsValue,
+    ) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method, js_name = signMessage)]
+    #[wasm_bindgen(method, js_name = signMessage, catch)]
     pub fn sign_message(
         this: &Backpack,
         message: &JsValue,
         public_key: &JsValue,
         uuid: &JsValue,
-    ) -> Promise;
+    ) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn on(this: &Backpack, event: &str, listener: &JsValue) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = removeListener, catch)]
+    pub fn remove_listener(this: &Backpack, event: &str, listener: &JsValue)
+        -> Result<(), JsValue>;
 
     #[wasm_bindgen(method, getter, js_name = isBackpack)]
     pub fn is_backpack(this: &Backpack) -> bool;
@@ -105,8 +113,8 @@ extern "C" {
     #[wasm_bindgen(method, getter, js_name = isXnft)]
     pub fn is_xnft(this: &Backpack) -> bool;
 
-    #[wasm_bindgen(method, getter)]
-    pub fn publicKey(this: &Backpack) -> JsValue;
+    #[wasm_bindgen(method, getter, catch)]
+    pub fn publicKey(this: &Backpack) -> Result<JsValue, JsValue>;
 
     #[wasm_bindgen(method, getter)]
     pub fn connection(this: &Backpack) -> JsValue;
@@ -3,9 +3,6 @@ use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
 extern "C" {
-    #[wasm_bindgen(js_namespace = window, js_name = solflare)]
-    pub static SOLFLARE: Solflare;
-
     #[wasm_bindgen(extends = Object)]
     pub type Solflare;
 
@@ -15,8 +12,8 @@ extern "C" {
     #[wasm_bindgen(method, getter, js_name = isConnected)]
     pub fn is_connected(this: &Solflare) -> bool;
 
-    #[wasm_bindgen(method, getter)]
-    pub fn publicKey(this: &Solflare) -> JsValue;
+    #[wasm_bindgen(method, getter, catch)]
+    pub fn publicKey(this: &Solflare) -> Result<JsValue, JsValue>;
 
     #[wasm_bindgen(method, getter, js_name = priorityFeesSupported)]
     pub fn priority_fees_supported(this: &Solflare) -> bool;
@@ -24,33 +21,40 @@ extern "C" {
     #[wasm_bindgen(method, getter, js_name = autoApprove)]
     pub fn auto_approve(this: &Solflare) -> bool;
 
-    #[wasm_bindgen(method)]
-    pub fn request(this: &Solflare, options: &JsValue) -> Promise;
+    #[wasm_bindgen(method, catch)]
+    pub fn request(this: &Solflare, options: &JsValue) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method)]
-    pub fn connect(this: &Solflare, options: &JsValue) -> Promise;
+    #[wasm_bindgen(method, catch)]
+    pub fn connect(this: &Solflare, options: &JsValue) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method)]
-    pub fn disconnect(this: &Solflare) -> Promise;
+    #[wasm_bindgen(method, catch)]
+    pub fn disconnect(this: &Solflare) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method, js_name = signTransaction)]
-    pub fn sign_transaction(this: &Solflare, transaction: &JsValue) -> Promise;
+    #[wasm_bindgen(method, js_name = signTransaction, catch)]
+    pub fn sign_transaction(this: &Solflare, transaction: &JsValue) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method, js_name = signMessage)]
-    pub fn sign_message(this: &Solflare, message: &JsValue) -> Promise;
+    #[wasm_bindgen(method, js_name = signMessage, catch)]
+    pub fn sign_message(
+        this: &Solflare,
+        message: &JsValue,
+        display: &JsValue,
+    ) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method, js_name = signAllTransactions)]
-    pub fn sign_all_transactions(this: &Solflare, transactions: &JsValue) -> Promise;
+    #[wasm_bindgen(method, js_name = signAllTransactions, catch)]
+    pub fn sign_all_transactions(
+        this: &Solflare,
+        transactions: &JsValue,
+    ) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method, js_name = signAndSendTransaction)]
+    #[wasm_bindgen(method, js_name = signAndSendTransaction, catch)]
     pub fn sign_and_send_transaction(
         this: &Solflare,
         transaction: &JsValue,
         options: &JsValue,
-    ) -> Promise;
+    ) -> Result<Promise, JsValue>;
 
-    #[wasm_bindgen(method)]
-    pub fn on(this: &Solflare, event: &JsValue, listener: &JsValue);
+    #[wasm_bindgen(method, catch)]
+    pub fn on(this: &Solflare, event: &str, listener: &JsValue) -> Result<(), JsValue>;
 
     #[wasm_bindgen(method, js_name = addEventListener)]
     pub fn add_event_listener(this: &Solflare, event: &JsValue, listener: &JsValue);
@@ -67,8 +71,9 @@ extern "C" {
     #[wasm_bindgen(method, js_name = removeEventListener)]
     pub fn remove_event_listener(this: &Solflare, event: &JsValue, listener: &JsValue);
 
-    #[wasm_bindgen(method, js_name = removeListener)]
-    pub fn remove_listener(this: &Solflare, event: &JsValue, listener: &JsValue);
+    #[wasm_bindgen(method, js_name = removeListener, catch)]
+    pub fn remove_listener(this: &Solflare, event: &str, listener: &JsValue)
+        -> Result<(), JsValue>;
 
     #[wasm_bindgen(method, js_name = eventNames)]
     pub fn event_names(this: &Solflare) -> Array;
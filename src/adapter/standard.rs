@@ -0,0 +1,117 @@
+use js_sys::{Object, Promise};
+use wasm_bindgen::prelude::*;
+
+pub const APP_READY_EVENT: &str = "wallet-standard:app-ready";
+pub const REGISTER_WALLET_EVENT: &str = "wallet-standard:register-wallet";
+
+pub const STANDARD_CONNECT: &str = "standard:connect";
+pub const STANDARD_DISCONNECT: &str = "standard:disconnect";
+pub const STANDARD_EVENTS: &str = "standard:events";
+pub const SOLANA_SIGN_AND_SEND_TRANSACTION: &str = "solana:signAndSendTransaction";
+pub const SOLANA_SIGN_TRANSACTION: &str = "solana:signTransaction";
+pub const SOLANA_SIGN_MESSAGE: &str = "solana:signMessage";
+pub const SOLANA_SIGN_IN: &str = "solana:signIn";
+
+pub const SOLANA_MAINNET_CHAIN: &str = "solana:mainnet";
+pub const SOLANA_DEVNET_CHAIN: &str = "solana:devnet";
+pub const SOLANA_TESTNET_CHAIN: &str = "solana:testnet";
+pub const SOLANA_LOCALNET_CHAIN: &str = "solana:localnet";
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Clone, Debug)]
+    pub type StandardWallet;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn version(this: &StandardWallet) -> JsValue;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn name(this: &StandardWallet) -> JsValue;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn icon(this: &StandardWallet) -> JsValue;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn chains(this: &StandardWallet) -> JsValue;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn features(this: &StandardWallet) -> JsValue;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn accounts(this: &StandardWallet) -> JsValue;
+
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Clone, Debug)]
+    pub type StandardWalletAccount;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn address(this: &StandardWalletAccount) -> JsValue;
+
+    #[wasm_bindgen(method, getter, js_name = publicKey)]
+    pub fn public_key(this: &StandardWalletAccount) -> JsValue;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn chains(this: &StandardWalletAccount) -> JsValue;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn features(this: &StandardWalletAccount) -> JsValue;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn label(this: &StandardWalletAccount) -> JsValue;
+
+    #[wasm_bindgen(extends = Object)]
+    pub type StandardConnectFeature;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn connect(this: &StandardConnectFeature, input: &JsValue) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(extends = Object)]
+    pub type StandardDisconnectFeature;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn disconnect(this: &StandardDisconnectFeature) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(extends = Object)]
+    pub type StandardEventsFeature;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn on(
+        this: &StandardEventsFeature,
+        event: &str,
+        listener: &JsValue,
+    ) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(extends = Object)]
+    pub type SolanaSignAndSendTransactionFeature;
+
+    #[wasm_bindgen(method, js_name = signAndSendTransaction, catch)]
+    pub fn sign_and_send_transaction(
+        this: &SolanaSignAndSendTransactionFeature,
+        input: &JsValue,
+    ) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(extends = Object)]
+    pub type SolanaSignTransactionFeature;
+
+    #[wasm_bindgen(method, js_name = signTransaction, catch)]
+    pub fn sign_transaction(
+        this: &SolanaSignTransactionFeature,
+        input: &JsValue,
+    ) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(extends = Object)]
+    pub type SolanaSignMessageFeature;
+
+    #[wasm_bindgen(method, js_name = signMessage, catch)]
+    pub fn sign_message(
+        this: &SolanaSignMessageFeature,
+        input: &JsValue,
+    ) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(extends = Object)]
+    pub type SolanaSignInFeature;
+
+    #[wasm_bindgen(method, js_name = signIn, catch)]
+    pub fn sign_in(this: &SolanaSignInFeature, input: &JsValue) -> Result<Promise, JsValue>;
+}
@@ -1,5 +1,28 @@
+#[cfg(feature = "web")]
+pub mod adapters;
+#[cfg(feature = "web")]
+pub mod client;
+#[cfg(feature = "web")]
+pub mod confirmation;
 pub mod error;
+#[cfg(feature = "web")]
+pub mod events;
+#[cfg(feature = "web")]
+pub(crate) mod listeners;
+#[cfg(feature = "web")]
+pub mod queue;
+#[cfg(feature = "web")]
+pub mod rebroadcast;
 pub mod response;
+#[cfg(feature = "web")]
+pub mod simulation;
+pub mod siws;
+#[cfg(feature = "web")]
+pub mod standard;
+#[cfg(feature = "web")]
+pub mod timeout;
+#[cfg(feature = "web")]
 pub mod traits;
 pub mod transaction;
+#[cfg(feature = "web")]
 pub mod wallet;
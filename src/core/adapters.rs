@@ -0,0 +1,314 @@
+//! Per-wallet adapters that implement only the capability traits their wallet supports, so
+//! generic code can be written against [`SignerWalletAdapter`], [`MessageSignerWalletAdapter`]
+//! or [`SignInMessageSignerWalletAdapter`].
+//!
+//! Each adapter wraps a [`BaseWalletAdapter`] and converts to and from it, so it can be used
+//! with the providers and forms. Wallet Standard wallets declare their features one by one, so
+//! they get one adapter per capability. These are handles to the same wallet when converted from
+//! clones of one adapter.
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::sync::Arc;
+
+use crate::{
+    adapter::standard::{SOLANA_SIGN_IN, SOLANA_SIGN_MESSAGE, SOLANA_SIGN_TRANSACTION},
+    core::{
+        client::WasmClient as RpcClient,
+        error::WalletError,
+        events::{EventStream, Subscription, WalletEvent},
+        response::{MessageDisplay, SignInInput, SignInOutput, SignMessageOutput},
+        traits::{
+            MessageSignerWalletAdapter, SignInMessageSignerWalletAdapter, SignerWalletAdapter,
+            WalletAdapter, WalletAdapterEvents,
+        },
+        transaction::{SendMode, SendOptions, TransactionOrVersionedTransaction},
+        wallet::{BaseWalletAdapter, Wallet, WalletReadyState},
+    },
+};
+
+macro_rules! wallet_adapter {
+    ($(#[$meta:meta])* $adapter:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, PartialEq)]
+        pub struct $adapter(BaseWalletAdapter);
+
+        impl $adapter {
+            pub fn inner(&self) -> &BaseWalletAdapter {
+                &self.0
+            }
+
+            pub fn inner_mut(&mut self) -> &mut BaseWalletAdapter {
+                &mut self.0
+            }
+
+            pub fn into_inner(self) -> BaseWalletAdapter {
+                self.0
+            }
+        }
+
+        impl From<$adapter> for BaseWalletAdapter {
+            fn from(adapter: $adapter) -> Self {
+                adapter.0
+            }
+        }
+
+        impl WalletAdapterEvents for $adapter {
+            fn subscribe(&self, listener: impl Fn(&WalletEvent) + 'static) -> Subscription {
+                self.0.subscribe(listener)
+            }
+
+            fn events(&self) -> EventStream {
+                self.0.events()
+            }
+
+            fn emit_connect(&mut self, public_key: Pubkey) {
+                self.0.emit_connect(public_key)
+            }
+
+            fn emit_disconnect(&mut self) {
+                self.0.emit_disconnect()
+            }
+
+            fn emit_error(&mut self, error: WalletError) {
+                self.0.emit_error(error)
+            }
+
+            fn ready_state_change(&mut self, ready_state: WalletReadyState) {
+                self.0.ready_state_change(ready_state)
+            }
+
+            fn emit_transaction_sent(&mut self, signature: Signature) {
+                self.0.emit_transaction_sent(signature)
+            }
+        }
+
+        impl WalletAdapter for $adapter {
+            fn name(&self) -> String {
+                self.0.name()
+            }
+
+            fn url(&self) -> String {
+                self.0.url()
+            }
+
+            fn icon(&self) -> String {
+                self.0.icon()
+            }
+
+            fn features(&self) -> Vec<String> {
+                self.0.features()
+            }
+
+            fn ready_state(&self) -> WalletReadyState {
+                self.0.ready_state()
+            }
+
+            fn public_key(&self) -> Option<Pubkey> {
+                self.0.public_key()
+            }
+
+            fn connecting(&self) -> bool {
+                self.0.connecting()
+            }
+
+            async fn auto_connect(&mut self) -> Result<bool, WalletError> {
+                self.0.auto_connect().await
+            }
+
+            async fn connect(&mut self) -> Result<bool, WalletError> {
+                self.0.connect().await
+            }
+
+            async fn disconnect(&mut self) -> Result<bool, WalletError> {
+                self.0.disconnect().await
+            }
+
+            async fn send_transaction_with_options(
+                &mut self,
+                client: Arc<RpcClient>,
+                transaction: TransactionOrVersionedTransaction,
+                options: SendOptions,
+            ) -> Result<Signature, WalletError> {
+                self.0
+                    .send_transaction_with_options(client, transaction, options)
+                    .await
+            }
+        }
+    };
+}
+
+macro_rules! signer_wallet_adapter {
+    ($adapter:ident) => {
+        impl SignerWalletAdapter for $adapter {
+            async fn sign_transaction(
+                &mut self,
+                transaction: impl Into<TransactionOrVersionedTransaction>,
+            ) -> Result<TransactionOrVersionedTransaction, WalletError> {
+                self.0.sign_transaction(transaction).await
+            }
+
+            async fn sign_all_transactions(
+                &mut self,
+                transactions: impl IntoIterator<Item = impl Into<TransactionOrVersionedTransaction>>,
+            ) -> Result<Vec<Result<TransactionOrVersionedTransaction, WalletError>>, WalletError>
+            {
+                self.0.sign_all_transactions(transactions).await
+            }
+
+            async fn sign_send_transaction_with_options(
+                &mut self,
+                transaction: impl Into<TransactionOrVersionedTransaction>,
+                options: SendOptions,
+            ) -> Result<Signature, WalletError> {
+                self.0
+                    .sign_send_transaction_with_options(transaction, options)
+                    .await
+            }
+
+            async fn sign_send_all_transactions_with_options(
+                &mut self,
+                transactions: impl IntoIterator<Item = impl Into<TransactionOrVersionedTransaction>>,
+                mode: SendMode,
+                options: SendOptions,
+            ) -> Result<Vec<Result<Signature, WalletError>>, WalletError> {
+                self.0
+                    .sign_send_all_transactions_with_options(transactions, mode, options)
+                    .await
+            }
+        }
+    };
+}
+
+macro_rules! message_signer_wallet_adapter {
+    ($adapter:ident) => {
+        impl MessageSignerWalletAdapter for $adapter {
+            async fn sign_message_with_display(
+                &mut self,
+                message: &[u8],
+                display: MessageDisplay,
+            ) -> Result<SignMessageOutput, WalletError> {
+                self.0.sign_message_with_display(message, display).await
+            }
+        }
+    };
+}
+
+macro_rules! sign_in_wallet_adapter {
+    ($adapter:ident) => {
+        impl SignInMessageSignerWalletAdapter for $adapter {
+            async fn sign_in(&mut self, input: SignInInput) -> Result<SignInOutput, WalletError> {
+                self.0.sign_in(input).await
+            }
+        }
+    };
+}
+
+/// Implements the constructors of an adapter for one of the built-in wallets.
+macro_rules! builtin_wallet_adapter {
+    ($adapter:ident, $wallet:expr) => {
+        impl Default for $adapter {
+            fn default() -> Self {
+                Self($wallet.into())
+            }
+        }
+
+        impl $adapter {
+            pub fn new() -> Self {
+                Self::default()
+            }
+        }
+
+        impl TryFrom<BaseWalletAdapter> for $adapter {
+            type Error = WalletError;
+
+            fn try_from(adapter: BaseWalletAdapter) -> Result<Self, Self::Error> {
+                match adapter.wallet() {
+                    wallet if *wallet == $wallet => Ok(Self(adapter)),
+                    _ => Err(WalletError::WalletConfigError),
+                }
+            }
+        }
+    };
+}
+
+wallet_adapter!(
+    /// Phantom: signs transactions and messages, and signs in natively.
+    PhantomWalletAdapter
+);
+builtin_wallet_adapter!(PhantomWalletAdapter, Wallet::Phantom);
+signer_wallet_adapter!(PhantomWalletAdapter);
+message_signer_wallet_adapter!(PhantomWalletAdapter);
+sign_in_wallet_adapter!(PhantomWalletAdapter);
+
+wallet_adapter!(
+    /// Solflare: signs transactions and messages. It has no sign in of its own;
+    /// [`BaseWalletAdapter::sign_in`] builds one on top of message signing.
+    SolflareWalletAdapter
+);
+builtin_wallet_adapter!(SolflareWalletAdapter, Wallet::Solflare);
+signer_wallet_adapter!(SolflareWalletAdapter);
+message_signer_wallet_adapter!(SolflareWalletAdapter);
+
+wallet_adapter!(
+    /// Backpack: signs transactions and messages, and signs in natively.
+    BackpackWalletAdapter
+);
+builtin_wallet_adapter!(BackpackWalletAdapter, Wallet::Backpack);
+signer_wallet_adapter!(BackpackWalletAdapter);
+message_signer_wallet_adapter!(BackpackWalletAdapter);
+sign_in_wallet_adapter!(BackpackWalletAdapter);
+
+/// Implements the conversion of an adapter for Wallet Standard wallets that declare every one of
+/// `features`.
+macro_rules! standard_wallet_adapter {
+    ($adapter:ident, [$($feature:expr),*]) => {
+        impl TryFrom<BaseWalletAdapter> for $adapter {
+            type Error = WalletError;
+
+            fn try_from(adapter: BaseWalletAdapter) -> Result<Self, Self::Error> {
+                let required: &[&str] = &[$($feature),*];
+                let features = adapter.features();
+                let supported = matches!(adapter.wallet(), Wallet::Standard(_))
+                    && required
+                        .iter()
+                        .all(|feature| features.iter().any(|name| name == feature));
+
+                if supported {
+                    Ok(Self(adapter))
+                } else {
+                    Err(WalletError::WalletConfigError)
+                }
+            }
+        }
+    };
+}
+
+wallet_adapter!(
+    /// Any Wallet Standard wallet, for code that only connects. See the other `Standard*`
+    /// adapters for what the wallet declares it can do.
+    StandardWalletAdapter
+);
+standard_wallet_adapter!(StandardWalletAdapter, []);
+
+wallet_adapter!(
+    /// A Wallet Standard wallet with `solana:signTransaction`. Transactions are sent with
+    /// `solana:signAndSendTransaction` when the wallet has it, and through the adapter's
+    /// connection otherwise.
+    StandardSignerWalletAdapter
+);
+standard_wallet_adapter!(StandardSignerWalletAdapter, [SOLANA_SIGN_TRANSACTION]);
+signer_wallet_adapter!(StandardSignerWalletAdapter);
+
+wallet_adapter!(
+    /// A Wallet Standard wallet with `solana:signMessage`.
+    StandardMessageSignerWalletAdapter
+);
+standard_wallet_adapter!(StandardMessageSignerWalletAdapter, [SOLANA_SIGN_MESSAGE]);
+message_signer_wallet_adapter!(StandardMessageSignerWalletAdapter);
+
+wallet_adapter!(
+    /// A Wallet Standard wallet with `solana:signIn`.
+    StandardSignInWalletAdapter
+);
+standard_wallet_adapter!(StandardSignInWalletAdapter, [SOLANA_SIGN_IN]);
+sign_in_wallet_adapter!(StandardSignInWalletAdapter);
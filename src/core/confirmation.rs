@@ -0,0 +1,254 @@
+//! Tracking sent transactions until they reach a commitment level or their blockhash expires.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, hash::Hash, signature::Signature,
+    transaction::TransactionError,
+};
+
+use crate::core::{
+    client::WasmClient as RpcClient,
+    error::{WalletError, WalletOperation},
+    events::{WalletEvent, WalletEvents},
+    timeout::sleep,
+};
+
+/// How often the status of a transaction is polled.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How far the cluster got with a transaction, from least to most final.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfirmationStatus {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl ConfirmationStatus {
+    /// Whether a transaction this far along satisfies `commitment`.
+    pub fn satisfies(&self, commitment: CommitmentConfig) -> bool {
+        if commitment.is_finalized() {
+            *self == ConfirmationStatus::Finalized
+        } else if commitment.is_confirmed() {
+            *self >= ConfirmationStatus::Confirmed
+        } else {
+            true
+        }
+    }
+}
+
+/// Where a sent transaction stands.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Confirmation {
+    pub signature: Signature,
+    pub status: ConfirmationStatus,
+    /// The slot the transaction was processed in.
+    pub slot: u64,
+    /// How many blocks have confirmed the transaction, `None` once it is finalized.
+    pub confirmations: Option<u64>,
+    /// Why the transaction failed, `None` if it succeeded.
+    pub error: Option<TransactionError>,
+}
+
+impl Confirmation {
+    /// The confirmation of a transaction that succeeded, or
+    /// [`WalletError::WalletTransactionFailedError`] for one that failed.
+    pub fn into_result(self) -> Result<Confirmation, WalletError> {
+        match self.error {
+            Some(error) => {
+                log::error!("Transaction {} failed: {}", self.signature, error);
+                Err(WalletError::WalletTransactionFailedError {
+                    signature: self.signature,
+                    error,
+                })
+            }
+            None => Ok(self),
+        }
+    }
+}
+
+/// An entry of a `getSignatureStatuses` reply.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcSignatureStatus {
+    slot: u64,
+    confirmations: Option<u64>,
+    err: Option<TransactionError>,
+    confirmation_status: Option<ConfirmationStatus>,
+}
+
+impl RpcSignatureStatus {
+    fn confirmation(self, signature: Signature) -> Confirmation {
+        Confirmation {
+            signature,
+            // Nodes that predate `confirmationStatus` only report confirmations, which are
+            // gone once the transaction is rooted.
+            status: self
+                .confirmation_status
+                .unwrap_or(match self.confirmations {
+                    Some(0) => ConfirmationStatus::Processed,
+                    Some(_) => ConfirmationStatus::Confirmed,
+                    None => ConfirmationStatus::Finalized,
+                }),
+            slot: self.slot,
+            confirmations: self.confirmations,
+            error: self.err,
+        }
+    }
+}
+
+/// When a transaction can no longer land.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Expiry {
+    pub(crate) blockhash: Option<Hash>,
+    pub(crate) last_valid_block_height: Option<u64>,
+}
+
+impl Expiry {
+    async fn has_passed(
+        &self,
+        client: &RpcClient,
+        commitment: CommitmentConfig,
+    ) -> Result<bool, WalletError> {
+        let expired = match (self.last_valid_block_height, self.blockhash) {
+            (Some(last_valid_block_height), _) => client
+                .get_block_height_with_commitment(commitment)
+                .await
+                .map(|height| height > last_valid_block_height),
+            (None, Some(blockhash)) => client
+                .is_blockhash_valid(&blockhash, commitment)
+                .await
+                .map(|valid| !valid),
+            (None, None) => return Ok(false),
+        };
+
+        expired.map_err(|err| {
+            log::error!("Failed to check the blockhash of a transaction: {:?}", err);
+            WalletError::from_rpc(WalletOperation::ConfirmTransaction, &err)
+        })
+    }
+}
+
+/// Polls the status of `signature` until it reaches `commitment`, emitting
+/// [`WalletEvent::ConfirmationProgress`] whenever it changes.
+///
+/// Fails with [`WalletError::WalletTransactionExpiredError`] once the transaction is not
+/// found and its blockhash expired.
+pub(crate) async fn confirm(
+    client: &RpcClient,
+    events: &WalletEvents,
+    signature: Signature,
+    commitment: CommitmentConfig,
+    expiry: Expiry,
+) -> Result<Confirmation, WalletError> {
+    let mut last = None;
+
+    loop {
+        let status = client.signature_status(&signature).await.map_err(|err| {
+            log::error!("Failed to get the status of {}: {:?}", signature, err);
+            WalletError::from_rpc(WalletOperation::ConfirmTransaction, &err)
+        })?;
+
+        match status {
+            Some(status) => {
+                let confirmation = status.confirmation(signature);
+                if last.as_ref() != Some(&confirmation) {
+                    events.emit(WalletEvent::ConfirmationProgress(confirmation.clone()));
+                    last = Some(confirmation.clone());
+                }
+                if confirmation.status.satisfies(commitment) {
+                    return Ok(confirmation);
+                }
+            }
+            // Checked only while the transaction is not found, as it may still land until
+            // the height the cluster reports passes the last valid one.
+            None if expiry.has_passed(client, commitment).await? => {
+                log::error!("Transaction {} expired", signature);
+                return Err(WalletError::WalletTransactionExpiredError { signature });
+            }
+            None => {}
+        }
+
+        sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use solana_sdk::instruction::InstructionError;
+
+    use super::*;
+
+    fn confirmation(status: serde_json::Value) -> Confirmation {
+        serde_json::from_value::<RpcSignatureStatus>(status)
+            .unwrap()
+            .confirmation(Signature::from([1; 64]))
+    }
+
+    #[test]
+    fn reads_the_status_of_old_and_new_nodes() {
+        let confirmed = confirmation(json!({
+            "slot": 5,
+            "confirmations": 3,
+            "err": null,
+            "confirmationStatus": "confirmed",
+        }));
+        assert_eq!(confirmed.status, ConfirmationStatus::Confirmed);
+        assert_eq!(confirmed.slot, 5);
+        assert_eq!(confirmed.confirmations, Some(3));
+
+        for (confirmations, status) in [
+            (json!(0), ConfirmationStatus::Processed),
+            (json!(2), ConfirmationStatus::Confirmed),
+            (json!(null), ConfirmationStatus::Finalized),
+        ] {
+            let confirmation = confirmation(json!({
+                "slot": 5,
+                "confirmations": confirmations,
+                "err": null,
+            }));
+            assert_eq!(confirmation.status, status);
+        }
+    }
+
+    #[test]
+    fn statuses_satisfy_the_commitments_they_reach() {
+        use ConfirmationStatus::*;
+
+        assert!(Processed.satisfies(CommitmentConfig::processed()));
+        assert!(!Processed.satisfies(CommitmentConfig::confirmed()));
+        assert!(Confirmed.satisfies(CommitmentConfig::confirmed()));
+        assert!(!Confirmed.satisfies(CommitmentConfig::finalized()));
+        assert!(Finalized.satisfies(CommitmentConfig::finalized()));
+        assert!(Finalized.satisfies(CommitmentConfig::processed()));
+    }
+
+    #[test]
+    fn maps_failed_transactions_to_an_error() {
+        let succeeded = confirmation(json!({
+            "slot": 5,
+            "confirmations": 1,
+            "err": null,
+            "confirmationStatus": "confirmed",
+        }));
+        assert_eq!(succeeded.clone().into_result(), Ok(succeeded));
+
+        let failed = confirmation(json!({
+            "slot": 5,
+            "confirmations": 1,
+            "err": { "InstructionError": [0, { "Custom": 1 }] },
+            "confirmationStatus": "confirmed",
+        }));
+        assert_eq!(
+            failed.into_result(),
+            Err(WalletError::WalletTransactionFailedError {
+                signature: Signature::from([1; 64]),
+                error: TransactionError::InstructionError(0, InstructionError::Custom(1)),
+            })
+        );
+    }
+}
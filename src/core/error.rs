@@ -1,7 +1,42 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use thiserror::Error;
 
-#[derive(Error, Debug, Serialize, Deserialize)]
+/// The wallet operation an error came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WalletOperation {
+    Connect,
+    Disconnect,
+    SignTransaction,
+    SignAllTransactions,
+    SignAndSendTransaction,
+    SendTransaction,
+    SignMessage,
+    SignIn,
+    GetLatestBlockhash,
+    SimulateTransaction,
+    ConfirmTransaction,
+}
+
+impl fmt::Display for WalletOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            WalletOperation::Connect => "connect",
+            WalletOperation::Disconnect => "disconnect",
+            WalletOperation::SignTransaction => "sign transaction",
+            WalletOperation::SignAllTransactions => "sign all transactions",
+            WalletOperation::SignAndSendTransaction => "sign and send transaction",
+            WalletOperation::SendTransaction => "send transaction",
+            WalletOperation::SignMessage => "sign message",
+            WalletOperation::SignIn => "sign in",
+            WalletOperation::GetLatestBlockhash => "get latest blockhash",
+            WalletOperation::SimulateTransaction => "simulate transaction",
+            WalletOperation::ConfirmTransaction => "confirm transaction",
+        })
+    }
+}
+
+#[derive(Error, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum WalletError {
     #[error("Wallet not connected")]
     WalletNotConnectedError,
@@ -29,12 +64,160 @@ pub enum WalletError {
     WalletSignMessageError,
     #[error("Failed to sign in")]
     WalletSignInError,
+    #[error("Wallet returned an invalid signature")]
+    WalletInvalidSignatureError,
     #[error("Wallet operation timed out")]
     WalletTimeoutError,
+    #[error("Wallet operation cancelled")]
+    WalletCancelledError,
+    /// Another request is waiting on the wallet and the adapter rejects rather than queues.
+    #[error("Another wallet request is pending")]
+    WalletRequestPendingError,
+    /// The transaction failed its simulation, so the wallet was not asked to sign it.
+    #[error("Transaction simulation failed: {error}")]
+    WalletSimulationError {
+        error: solana_sdk::transaction::TransactionError,
+        logs: Vec<String>,
+    },
+    /// The blockhash of the transaction expired before it landed, so it never will.
+    #[error("Transaction {signature} expired")]
+    WalletTransactionExpiredError {
+        signature: solana_sdk::signature::Signature,
+    },
+    /// The connected account does not support the chain of the adapter's cluster.
+    #[error("Wallet account does not support {chain}")]
+    WalletChainError { chain: String },
+    /// The transaction landed but failed, so none of its instructions took effect.
+    #[error("Transaction {signature} failed: {error}")]
+    WalletTransactionFailedError {
+        signature: solana_sdk::signature::Signature,
+        error: solana_sdk::transaction::TransactionError,
+    },
     #[error("Wallet window blocked")]
     WalletWindowBlockedError,
     #[error("Wallet window closed")]
     WalletWindowClosedError,
+    /// The wallet rejected the request, e.g. because the user cancelled it.
+    #[error("Wallet failed to {operation}: {message}")]
+    WalletProviderError {
+        operation: WalletOperation,
+        code: Option<i64>,
+        message: String,
+    },
+    /// The RPC node rejected a request, e.g. a transaction that failed preflight.
+    #[error("RPC node failed to {operation}: {message}")]
+    WalletRpcError {
+        operation: WalletOperation,
+        code: Option<i64>,
+        message: String,
+    },
+}
+
+impl WalletError {
+    /// The user rejected the request.
+    pub const USER_REJECTED: i64 = 4001;
+    /// The requested account or method has not been authorized by the user.
+    pub const UNAUTHORIZED: i64 = 4100;
+    /// The wallet does not support the requested method.
+    pub const UNSUPPORTED_METHOD: i64 = 4200;
+    /// The wallet is not connected to any chain.
+    pub const DISCONNECTED: i64 = 4900;
+    /// Missing or invalid request parameters.
+    pub const INVALID_INPUT: i64 = -32000;
+    /// The requested resource is not available.
+    pub const RESOURCE_NOT_AVAILABLE: i64 = -32002;
+    /// The transaction was rejected.
+    pub const TRANSACTION_REJECTED: i64 = -32003;
+    /// The requested method does not exist.
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    /// Something went wrong inside the wallet.
+    pub const INTERNAL_ERROR: i64 = -32603;
+
+    /// The error code reported by the wallet or the RPC node, if any.
+    pub fn code(&self) -> Option<i64> {
+        match self {
+            WalletError::WalletProviderError { code, .. }
+            | WalletError::WalletRpcError { code, .. } => *code,
+            _ => None,
+        }
+    }
+
+    /// The operation that failed, for errors reported by the wallet or the RPC node.
+    pub fn operation(&self) -> Option<WalletOperation> {
+        match self {
+            WalletError::WalletProviderError { operation, .. }
+            | WalletError::WalletRpcError { operation, .. } => Some(*operation),
+            _ => None,
+        }
+    }
+
+    pub fn is_user_rejection(&self) -> bool {
+        matches!(self, WalletError::WalletProviderError { .. })
+            && self.code() == Some(Self::USER_REJECTED)
+    }
+
+    /// Keeps the `code` and `message` of a JS rejection from the wallet.
+    #[cfg(feature = "web")]
+    pub(crate) fn from_js(operation: WalletOperation, error: &wasm_bindgen::JsValue) -> Self {
+        let field = |key: &str| {
+            js_sys::Reflect::get(error, &wasm_bindgen::JsValue::from_str(key))
+                .ok()
+                .filter(|value| !value.is_undefined())
+        };
+
+        WalletError::WalletProviderError {
+            operation,
+            code: field("code")
+                .and_then(|code| code.as_f64())
+                .map(|code| code as i64),
+            message: error
+                .as_string()
+                .or_else(|| field("message").and_then(|message| message.as_string()))
+                .unwrap_or_else(|| format!("{:?}", error)),
+        }
+    }
+
+    /// Keeps the JSON-RPC error `code` and `message` of a failed RPC request.
+    #[cfg(feature = "web")]
+    pub(crate) fn from_rpc(
+        operation: WalletOperation,
+        error: &solana_client_wasm::ClientError,
+    ) -> Self {
+        let details = serde_json::to_value(error).unwrap_or_default();
+
+        WalletError::WalletRpcError {
+            operation,
+            code: details["error"]["code"].as_i64(),
+            message: details["error"]["message"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| error.to_string()),
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, WalletError>;
+
+/// Why a Sign In With Solana output was rejected by [`crate::core::siws::verify_sign_in`].
+#[cfg(feature = "verify")]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SignInVerificationError {
+    #[error("Signature does not match the account and signed message")]
+    InvalidSignature,
+    #[error("Signed message is not a valid Sign In With Solana message")]
+    MalformedMessage,
+    #[error("Signed message is for a different account")]
+    AccountMismatch,
+    #[error("Signed message is for a different domain")]
+    DomainMismatch,
+    #[error("Signed message has a different nonce")]
+    NonceMismatch,
+    #[error("Signed message is for a different chain")]
+    ChainMismatch,
+    #[error("Signed message has a different {0}")]
+    FieldMismatch(&'static str),
+    #[error("Sign in has expired")]
+    Expired,
+    #[error("Sign in is not valid yet")]
+    NotYetValid,
+}
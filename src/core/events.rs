@@ -0,0 +1,259 @@
+//! Typed events emitted by wallet adapters.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    pin::Pin,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Weak,
+    },
+    task::{Context, Poll},
+};
+
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver},
+    Stream, StreamExt,
+};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::core::{confirmation::Confirmation, error::WalletError, wallet::WalletReadyState};
+
+type Listener = Rc<dyn Fn(&WalletEvent)>;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    // Listeners are kept out of the adapters, which have to be `Send` and `Sync`, so UI code
+    // can subscribe with closures over its own state.
+    static LISTENERS: RefCell<HashMap<u64, Vec<Registration>>> = RefCell::new(HashMap::new());
+}
+
+struct Registration {
+    id: u64,
+    /// Whether the listener keeps the wallet's ready state polled.
+    watches: bool,
+    listener: Listener,
+}
+
+/// Something that happened to a wallet.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WalletEvent {
+    /// The wallet connected with this account.
+    Connect(Pubkey),
+    /// The wallet disconnected, whether the app or the user asked for it.
+    Disconnect,
+    /// A wallet operation failed.
+    Error(WalletError),
+    /// The wallet was injected into the page or went away.
+    ReadyStateChange(WalletReadyState),
+    /// A transaction was submitted.
+    TransactionSent(Signature),
+    /// The active account changed, because the user switched accounts in the wallet or one was
+    /// selected with [`select_account`](crate::core::wallet::BaseWalletAdapter::select_account).
+    AccountChanged(Pubkey),
+    /// The wallet exposes a different list of accounts, e.g. once it connected.
+    AccountsChanged(Vec<Pubkey>),
+    /// A request started waiting on the wallet (`true`), or the last one settled (`false`).
+    PendingRequestChange(bool),
+    /// A transaction being confirmed was seen at a new status or by more blocks.
+    ConfirmationProgress(Confirmation),
+}
+
+/// The listeners of an adapter, shared by the adapter and its clones.
+#[derive(Clone, Default)]
+pub(crate) struct WalletEvents(Arc<Emitter>);
+
+struct Emitter {
+    id: u64,
+    /// Whether something polls the wallet's ready state for the listeners.
+    watched: AtomicBool,
+}
+
+impl Default for Emitter {
+    fn default() -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            watched: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Drop for Emitter {
+    fn drop(&mut self) {
+        let _listeners = LISTENERS.try_with(|listeners| listeners.borrow_mut().remove(&self.id));
+    }
+}
+
+/// The listeners of an adapter, without keeping the adapter's clones from dropping them.
+pub(crate) struct WeakWalletEvents(Weak<Emitter>);
+
+impl WeakWalletEvents {
+    pub(crate) fn upgrade(&self) -> Option<WalletEvents> {
+        self.0.upgrade().map(WalletEvents)
+    }
+}
+
+impl WalletEvents {
+    pub(crate) fn subscribe(&self, listener: impl Fn(&WalletEvent) + 'static) -> Subscription {
+        self.register(true, Rc::new(listener))
+    }
+
+    /// Subscribes without keeping the ready state polled, for the adapter's own bookkeeping.
+    #[cfg(any(test, feature = "dio", feature = "lep", feature = "yew"))]
+    pub(crate) fn subscribe_unwatched(
+        &self,
+        listener: impl Fn(&WalletEvent) + 'static,
+    ) -> Subscription {
+        self.register(false, Rc::new(listener))
+    }
+
+    fn register(&self, watches: bool, listener: Listener) -> Subscription {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        LISTENERS.with(|listeners| {
+            listeners
+                .borrow_mut()
+                .entry(self.0.id)
+                .or_default()
+                .push(Registration {
+                    id,
+                    watches,
+                    listener,
+                });
+        });
+
+        Subscription {
+            emitter: self.0.id,
+            id,
+        }
+    }
+
+    /// Whether a listener other than the adapter's own wants the ready state polled.
+    pub(crate) fn has_watchers(&self) -> bool {
+        LISTENERS.with(|listeners| {
+            listeners
+                .borrow()
+                .get(&self.0.id)
+                .is_some_and(|listeners| listeners.iter().any(|listener| listener.watches))
+        })
+    }
+
+    /// Marks the ready state as watched or not, returning whether it was.
+    pub(crate) fn set_watched(&self, watched: bool) -> bool {
+        self.0.watched.swap(watched, Ordering::Relaxed)
+    }
+
+    pub(crate) fn downgrade(&self) -> WeakWalletEvents {
+        WeakWalletEvents(Arc::downgrade(&self.0))
+    }
+
+    /// Calls every listener with `event`, in the order they subscribed.
+    pub(crate) fn emit(&self, event: WalletEvent) {
+        // Listeners may subscribe or unsubscribe while they run.
+        let listeners: Vec<Listener> = LISTENERS.with(|listeners| {
+            listeners
+                .borrow()
+                .get(&self.0.id)
+                .map(|listeners| {
+                    listeners
+                        .iter()
+                        .map(|registration| registration.listener.clone())
+                        .collect()
+                })
+                .unwrap_or_default()
+        });
+
+        for listener in listeners {
+            listener(&event);
+        }
+    }
+
+    pub(crate) fn stream(&self) -> EventStream {
+        let (sender, receiver) = unbounded();
+        let subscription = self.subscribe(move |event| {
+            let _ = sender.unbounded_send(event.clone());
+        });
+
+        EventStream {
+            receiver,
+            _subscription: subscription,
+        }
+    }
+}
+
+impl PartialEq for WalletEvents {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// A listener added with [`subscribe`](crate::core::traits::WalletAdapterEvents::subscribe).
+/// Dropping it removes the listener.
+#[must_use = "the listener is removed as soon as the subscription is dropped"]
+#[derive(Debug)]
+pub struct Subscription {
+    emitter: u64,
+    id: u64,
+}
+
+impl Subscription {
+    /// Keeps the listener for as long as the adapter lives.
+    pub fn forget(self) {
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        // The listener is dropped after the registry is released, as it may own subscriptions.
+        let _listener = LISTENERS.try_with(|listeners| {
+            let mut listeners = listeners.borrow_mut();
+            let listeners = listeners.get_mut(&self.emitter)?;
+            let index = listeners
+                .iter()
+                .position(|registration| registration.id == self.id)?;
+            Some(listeners.remove(index))
+        });
+    }
+}
+
+/// The events of an adapter, from the moment the stream was created. Ends when the adapter and
+/// all of its clones are dropped.
+#[derive(Debug)]
+pub struct EventStream {
+    receiver: UnboundedReceiver<WalletEvent>,
+    _subscription: Subscription,
+}
+
+impl Stream for EventStream {
+    type Item = WalletEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn only_subscriptions_keep_the_ready_state_watched() {
+        let events = WalletEvents::default();
+        let seen = Rc::new(Cell::new(0));
+        let counter = seen.clone();
+        let _bookkeeping = events.subscribe_unwatched(move |_| counter.set(counter.get() + 1));
+        assert!(!events.has_watchers());
+
+        let subscription = events.subscribe(|_| {});
+        assert!(events.has_watchers());
+        drop(subscription);
+        assert!(!events.has_watchers());
+
+        events.emit(WalletEvent::Disconnect);
+        assert_eq!(seen.get(), 1);
+    }
+}
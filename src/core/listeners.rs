@@ -0,0 +1,156 @@
+//! Listeners on the events wallets emit on their own, e.g. when the user switches accounts or
+//! disconnects from the wallet's UI.
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use js_sys::Reflect;
+use log::info;
+use wasm_bindgen::prelude::*;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::core::{
+    standard,
+    wallet::{backpack, js_public_key, phantom, solflare, Wallet},
+};
+
+thread_local! {
+    static NEXT_ID: RefCell<u64> = const { RefCell::new(0) };
+    static SUBSCRIPTIONS: RefCell<HashMap<u64, Vec<Cleanup>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// An event reported by the wallet itself.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ProviderEvent {
+    /// The wallet connected, with the account if it reported one.
+    Connect(Option<Pubkey>),
+    /// The user switched accounts; `None` when the wallet no longer exposes one.
+    AccountChanged(Option<Pubkey>),
+    /// The wallet exposes these accounts now, none if the site lost access to them.
+    AccountsChanged(Vec<Pubkey>),
+    Disconnect,
+}
+
+/// Listens for `wallet`'s events until [`unsubscribe`] is called or `handler` returns `false`.
+///
+/// Returns `None` when the wallet has no events to listen to.
+pub(crate) fn subscribe(
+    wallet: &Wallet,
+    handler: impl Fn(ProviderEvent) -> bool + 'static,
+) -> Option<u64> {
+    let id = NEXT_ID.with(|next_id| {
+        let mut next_id = next_id.borrow_mut();
+        *next_id += 1;
+        *next_id
+    });
+
+    let handler = move |event| {
+        if !handler(event) {
+            // Removing listeners while the wallet is dispatching to them is left to the next
+            // tick, as emitters differ in how they handle it.
+            wasm_bindgen_futures::spawn_local(async move { unsubscribe(id) });
+        }
+    };
+
+    let cleanups = match wallet {
+        Wallet::Standard(name) => subscribe_standard(name, handler),
+        Wallet::Phantom => subscribe_injected(handler, |event, listener| {
+            let phantom = phantom()?;
+            phantom.on(event, listener)?;
+            Ok(Box::new(move |event: &str, listener: &JsValue| {
+                let _ = phantom.remove_listener(event, listener);
+            }))
+        }),
+        Wallet::Solflare => subscribe_injected(handler, |event, listener| {
+            let solflare = solflare()?;
+            solflare.on(event, listener)?;
+            Ok(Box::new(move |event: &str, listener: &JsValue| {
+                let _ = solflare.remove_listener(event, listener);
+            }))
+        }),
+        Wallet::Backpack => subscribe_injected(handler, |event, listener| {
+            let backpack = backpack()?;
+            backpack.on(event, listener)?;
+            Ok(Box::new(move |event: &str, listener: &JsValue| {
+                let _ = backpack.remove_listener(event, listener);
+            }))
+        }),
+    };
+
+    if cleanups.is_empty() {
+        info!("{:?} does not report its own events", wallet);
+        return None;
+    }
+
+    SUBSCRIPTIONS.with(|subscriptions| subscriptions.borrow_mut().insert(id, cleanups));
+    Some(id)
+}
+
+/// Removes the listeners added by [`subscribe`].
+pub(crate) fn unsubscribe(id: u64) {
+    let cleanups = SUBSCRIPTIONS.with(|subscriptions| subscriptions.borrow_mut().remove(&id));
+
+    for cleanup in cleanups.into_iter().flatten() {
+        cleanup();
+    }
+}
+
+type Cleanup = Box<dyn FnOnce()>;
+type Remove = Box<dyn Fn(&str, &JsValue)>;
+
+/// Listens to the `connect`, `accountChanged` and `disconnect` events of an injected provider.
+fn subscribe_injected(
+    handler: impl Fn(ProviderEvent) + 'static,
+    on: impl Fn(&str, &JsValue) -> Result<Remove, JsValue>,
+) -> Vec<Cleanup> {
+    let handler = Rc::new(handler);
+
+    ["connect", "accountChanged", "disconnect"]
+        .into_iter()
+        .filter_map(|event| {
+            let handler = handler.clone();
+            let listener = Closure::<dyn Fn(JsValue)>::new(move |public_key: JsValue| {
+                let public_key = js_public_key(&public_key);
+                handler(match event {
+                    "connect" => ProviderEvent::Connect(public_key),
+                    "accountChanged" => ProviderEvent::AccountChanged(public_key),
+                    _ => ProviderEvent::Disconnect,
+                })
+            });
+
+            // The closure is kept until the listener is removed, and dropped along with the
+            // adapter state it holds.
+            match on(event, listener.as_ref()) {
+                Ok(remove) => Some(Box::new(move || remove(event, listener.as_ref())) as Cleanup),
+                Err(err) => {
+                    log::error!("Failed to listen to {} events: {:?}", event, err);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Listens to `standard:events` changes to the wallet's accounts.
+fn subscribe_standard(name: &str, handler: impl Fn(ProviderEvent) + 'static) -> Vec<Cleanup> {
+    let wallet = name.to_string();
+    let listener = Closure::<dyn Fn(JsValue)>::new(move |properties: JsValue| {
+        if Reflect::has(&properties, &JsValue::from_str("accounts")).unwrap_or(false) {
+            handler(ProviderEvent::AccountsChanged(standard::public_keys(
+                &wallet,
+            )));
+        }
+    });
+
+    match standard::on_change(name, listener.as_ref()) {
+        Ok(off) => vec![Box::new(move || {
+            let _ = off.call0(&JsValue::UNDEFINED);
+            drop(listener);
+        })],
+        Err(err) => {
+            log::error!("Failed to listen to {} events: {:?}", name, err);
+            Vec::new()
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! One request at a time per wallet, as wallets handle overlapping prompts badly.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use futures::lock::{Mutex, OwnedMutexGuard};
+
+use crate::core::{
+    error::WalletError,
+    events::{WalletEvent, WalletEvents},
+};
+
+/// What to do with a request made while another one is waiting on the wallet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RequestPolicy {
+    /// Wait for the pending requests to settle, in the order they were made.
+    #[default]
+    Queue,
+    /// Fail at once with [`WalletError::WalletRequestPendingError`].
+    Reject,
+}
+
+/// The requests of an adapter, shared with its clones.
+#[derive(Clone, Default)]
+pub(crate) struct RequestQueue {
+    lock: Arc<Mutex<()>>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl RequestQueue {
+    /// Waits for the wallet to be free, unless `policy` says to reject the request. The wallet
+    /// is free again once the returned guard is dropped.
+    pub(crate) async fn enter(
+        &self,
+        policy: RequestPolicy,
+        events: &WalletEvents,
+    ) -> Result<Request, WalletError> {
+        if policy == RequestPolicy::Reject {
+            let guard = self
+                .lock
+                .clone()
+                .try_lock_owned()
+                .ok_or(WalletError::WalletRequestPendingError)?;
+            return Ok(Request {
+                _guard: guard,
+                _pending: self.pend(events),
+            });
+        }
+
+        // Counted while queued too, so a request dropped before its turn is not left pending.
+        let pending = self.pend(events);
+        Ok(Request {
+            _guard: self.lock.clone().lock_owned().await,
+            _pending: pending,
+        })
+    }
+
+    pub(crate) fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::SeqCst) > 0
+    }
+
+    fn pend(&self, events: &WalletEvents) -> Pending {
+        if self.pending.fetch_add(1, Ordering::SeqCst) == 0 {
+            events.emit(WalletEvent::PendingRequestChange(true));
+        }
+        Pending {
+            pending: self.pending.clone(),
+            events: events.clone(),
+        }
+    }
+}
+
+impl PartialEq for RequestQueue {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.lock, &other.lock)
+    }
+}
+
+/// A request that has the wallet to itself.
+pub(crate) struct Request {
+    // Released before the count drops, so the next request in line keeps the queue pending.
+    _guard: OwnedMutexGuard<()>,
+    _pending: Pending,
+}
+
+struct Pending {
+    pending: Arc<AtomicUsize>,
+    events: WalletEvents,
+}
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        if self.pending.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.events.emit(WalletEvent::PendingRequestChange(false));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{executor::block_on, future::join};
+
+    use super::*;
+    use crate::core::timeout::CancelHandle;
+
+    #[test]
+    fn busy_wallets_reject_requests_under_the_reject_policy() {
+        let (queue, events) = (RequestQueue::default(), WalletEvents::default());
+        let _first = block_on(queue.enter(RequestPolicy::Reject, &events)).unwrap();
+
+        assert!(matches!(
+            block_on(queue.enter(RequestPolicy::Reject, &events)),
+            Err(WalletError::WalletRequestPendingError)
+        ));
+    }
+
+    #[test]
+    fn cancelling_a_queued_request_leaves_the_queue_to_the_others() {
+        let (queue, events) = (RequestQueue::default(), WalletEvents::default());
+        let cancel = CancelHandle::default();
+        let first = block_on(queue.enter(RequestPolicy::Queue, &events)).unwrap();
+
+        let (queued, _) = block_on(join(
+            cancel.run(None, queue.enter(RequestPolicy::Queue, &events)),
+            async { cancel.cancel() },
+        ));
+
+        assert!(matches!(queued, Err(WalletError::WalletCancelledError)));
+        assert!(queue.is_pending());
+        drop(first);
+        assert!(!queue.is_pending());
+    }
+}
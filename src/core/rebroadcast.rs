@@ -0,0 +1,139 @@
+//! Sending signed transactions again and again until they land, for when the cluster drops them.
+
+use std::{cell::Cell, pin::pin, time::Duration};
+
+use futures::future::{select, Either};
+use solana_sdk::{commitment_config::CommitmentLevel, signature::Signature};
+
+use crate::core::{
+    client::WasmClient as RpcClient,
+    confirmation::{self, Confirmation, Expiry},
+    error::{WalletError, WalletOperation},
+    events::{WalletEvent, WalletEvents},
+    timeout::sleep,
+    transaction::{SendOptions, TransactionOrVersionedTransaction},
+};
+
+/// How [`sign_send_transaction`](crate::core::wallet::BaseWalletAdapter::sign_send_transaction)
+/// gets a transaction to the cluster.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SendStrategy {
+    /// The wallet signs and submits the transaction.
+    #[default]
+    Wallet,
+    /// The wallet only signs; the adapter submits the transaction through its connection and
+    /// resends it until it confirms or its blockhash expires.
+    Rebroadcast(RebroadcastConfig),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RebroadcastConfig {
+    /// How long to wait between sends.
+    pub interval: Duration,
+    /// How many times the transaction is sent again at most. `None` keeps sending until it
+    /// confirms or expires.
+    pub max_retries: Option<usize>,
+    /// Skips the preflight checks of the first send. Resends always skip them, as a
+    /// transaction that already landed fails them.
+    pub skip_preflight: bool,
+    /// The commitment the preflight checks run at, the connection's by default.
+    pub preflight_commitment: Option<CommitmentLevel>,
+    /// The minimum slot the RPC node has to be at to run the preflight checks.
+    pub min_context_slot: Option<u64>,
+}
+
+impl Default for RebroadcastConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(2),
+            max_retries: None,
+            skip_preflight: false,
+            preflight_commitment: None,
+            min_context_slot: None,
+        }
+    }
+}
+
+impl RebroadcastConfig {
+    /// Applies the options given to a send, which take precedence over the config's.
+    pub(crate) fn with_options(self, options: &SendOptions) -> Self {
+        Self {
+            max_retries: options.max_retries.or(self.max_retries),
+            skip_preflight: options.skip_preflight || self.skip_preflight,
+            preflight_commitment: options.preflight_commitment.or(self.preflight_commitment),
+            min_context_slot: options.min_context_slot.or(self.min_context_slot),
+            ..self
+        }
+    }
+}
+
+/// How a rebroadcast transaction landed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RebroadcastReport {
+    pub signature: Signature,
+    /// How many times the transaction was sent after the first time.
+    pub resends: usize,
+    pub confirmation: Confirmation,
+}
+
+/// Sends the signed `transaction` through `client`, then again every `config.interval` until
+/// it reaches the client's commitment or `expiry` passes.
+pub(crate) async fn rebroadcast(
+    client: &RpcClient,
+    events: &WalletEvents,
+    transaction: &TransactionOrVersionedTransaction,
+    expiry: Expiry,
+    config: RebroadcastConfig,
+) -> Result<RebroadcastReport, WalletError> {
+    // The RPC node is told not to retry on its own, as the adapter does.
+    let options = SendOptions {
+        skip_preflight: config.skip_preflight,
+        preflight_commitment: config.preflight_commitment,
+        max_retries: Some(0),
+        min_context_slot: config.min_context_slot,
+    };
+    let send_config = client.send_config(&options);
+    let signature = client
+        .send_with_config(transaction, send_config)
+        .await
+        .map_err(|err| {
+            log::error!("Failed to send transaction: {:?}", err);
+            WalletError::from_rpc(WalletOperation::SendTransaction, &err)
+        })?;
+    events.emit(WalletEvent::TransactionSent(signature));
+
+    let resends = Cell::new(0);
+    let confirm = confirmation::confirm(
+        client,
+        events,
+        signature,
+        client.commitment_config(),
+        expiry,
+    );
+    let resend = async {
+        let resend_config = client.send_config(&SendOptions {
+            skip_preflight: true,
+            ..options
+        });
+        while config.max_retries.is_none_or(|max| resends.get() < max) {
+            sleep(config.interval).await;
+            resends.set(resends.get() + 1);
+            log::info!("Resending transaction {} ({})", signature, resends.get());
+            if let Err(err) = client.send_with_config(transaction, resend_config).await {
+                log::warn!("Failed to resend transaction {}: {:?}", signature, err);
+            }
+        }
+    };
+
+    let confirmation = match select(pin!(confirm), pin!(resend)).await {
+        Either::Left((confirmation, _)) => confirmation?,
+        // Out of retries: the transaction may still land.
+        Either::Right((_, confirm)) => confirm.await?,
+    };
+
+    Ok(RebroadcastReport {
+        signature,
+        resends: resends.get(),
+        confirmation,
+    })
+}
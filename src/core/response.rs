@@ -1,51 +1,135 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use solana_sdk::{bs58, pubkey::Pubkey, signature::Signature};
+
+/// The fields of a Sign In With Solana (SIWS) request. Every field is optional; wallets with
+/// native `signIn` fill in the ones left out, such as `domain` and `address`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignInInput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statement: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issued_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resources: Option<Vec<String>>,
+}
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct SignedMessage {
-    #[serde(rename = "type")]
-    message_type: String,
-    data: Vec<u8>,
+/// What the wallet returns from a sign in: the account, the exact message text it signed and
+/// the signature over it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignInOutput {
+    pub account: Pubkey,
+    pub signed_message: Vec<u8>,
+    pub signature: Signature,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Signature {
-    #[serde(rename = "type")]
-    signature_type: String,
-    data: Vec<u8>,
+/// How the wallet shows a message it is asked to sign.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageDisplay {
+    /// As text. Wallets reject messages that aren't valid UTF-8.
+    #[default]
+    Utf8,
+    /// As hex, for binary payloads.
+    Hex,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct MessageObject {
-    address: String,
-    #[serde(rename = "SignedMessage")]
-    signed_message: SignedMessage,
-    signature: Signature,
+impl MessageDisplay {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessageDisplay::Utf8 => "utf8",
+            MessageDisplay::Hex => "hex",
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct JsSignature {
-    #[serde(rename = "type")]
-    pub sig_type: String,
-    pub data: Vec<u8>,
+/// A signed message: the exact bytes the wallet signed and its signature over them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignMessageOutput {
+    pub signed_message: Vec<u8>,
+    pub signature: Signature,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Deserialize, Debug)]
 pub struct JsSignatureResponse {
-    pub signature: JsSignature,
+    pub signature: JsBytes,
     #[serde(rename = "publicKey")]
-    pub public_key: String,
+    pub public_key: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct SignaturesObject {
-    pub signatures: Vec<SignatureArray>,
+/// Byte arrays as they come out of `JSON.stringify`: a Node `Buffer`, an indexed `Uint8Array`,
+/// a plain array or a base58 string.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum JsBytes {
+    Buffer { data: Vec<u8> },
+    Array(Vec<u8>),
+    Indexed(BTreeMap<usize, u8>),
+    Base58(String),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct SignatureArray {
-    #[serde(flatten)]
-    pub signature: serde_json::Map<String, Value>,
+impl JsBytes {
+    pub fn to_vec(&self) -> Option<Vec<u8>> {
+        match self {
+            JsBytes::Buffer { data } | JsBytes::Array(data) => Some(data.clone()),
+            JsBytes::Indexed(map) => map
+                .keys()
+                .enumerate()
+                .all(|(index, key)| index == *key)
+                .then(|| map.values().copied().collect()),
+            JsBytes::Base58(string) => bs58::decode(string).into_vec().ok(),
+        }
+    }
+}
+
+/// One entry of a signed transaction's `signatures`: either the raw signature
+/// (`VersionedTransaction`) or a signature and its signer (legacy `Transaction`).
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum TransactionSignature {
+    WithPublicKey {
+        signature: Option<JsBytes>,
+        #[serde(rename = "publicKey")]
+        public_key: String,
+    },
+    Bytes(JsBytes),
+}
+
+/// A wallet's reply to `signTransaction` when it doesn't hand back the serialized transaction.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum SignedTransactionResponse {
+    Signatures {
+        signatures: Vec<TransactionSignature>,
+    },
+    Signature {
+        signature: JsBytes,
+        #[serde(rename = "publicKey")]
+        public_key: Option<String>,
+    },
 }
 
 #[derive(Deserialize, Debug)]
@@ -54,3 +138,10 @@ pub struct JsSignatureObject {
     #[serde(rename = "publicKey")]
     pub public_key: String,
 }
+
+/// Phantom's reply to `signAndSendAllTransactions`: a base58 signature per transaction, or
+/// whatever the wallet put in place of the ones that failed.
+#[derive(Deserialize, Debug)]
+pub struct JsSignaturesObject {
+    pub signatures: Vec<Value>,
+}
@@ -0,0 +1,187 @@
+//! Dry runs of transactions, so users are not prompted for ones that are bound to fail.
+
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, transaction::TransactionError};
+
+/// What a transaction would do if it were sent now.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SimulationReport {
+    /// Why the transaction would fail, `None` if it would succeed.
+    pub error: Option<TransactionError>,
+    /// The program logs, in order.
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    /// The accounts the transaction may write to, before and after it runs. Empty when the
+    /// simulation did not report them.
+    pub accounts: Vec<AccountChange>,
+}
+
+impl SimulationReport {
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// An account as it is before and after a simulated transaction. `None` when the account does
+/// not exist.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountChange {
+    pub pubkey: Pubkey,
+    pub before: Option<AccountState>,
+    pub after: Option<AccountState>,
+}
+
+impl AccountChange {
+    /// How many lamports the account gains, negative if it loses some.
+    pub fn lamports_delta(&self) -> i128 {
+        let lamports =
+            |state: &Option<AccountState>| state.as_ref().map_or(0, |state| state.lamports as i128);
+        lamports(&self.after) - lamports(&self.before)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountState {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+    pub executable: bool,
+}
+
+/// The `value` of a `simulateTransaction` reply. Wallets that simulate on their own answer with
+/// the same fields.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcSimulation {
+    err: Option<TransactionError>,
+    logs: Option<Vec<String>>,
+    accounts: Option<Vec<Option<RpcAccount>>>,
+    units_consumed: Option<u64>,
+}
+
+/// An account encoded as `base64`, as requested from the RPC node.
+#[derive(Deserialize)]
+pub(crate) struct RpcAccount {
+    lamports: u64,
+    owner: String,
+    data: (String, String),
+    executable: bool,
+}
+
+impl RpcAccount {
+    pub(crate) fn state(&self) -> Option<AccountState> {
+        Some(AccountState {
+            lamports: self.lamports,
+            owner: Pubkey::from_str(&self.owner).ok()?,
+            data: STANDARD.decode(&self.data.0).ok()?,
+            executable: self.executable,
+        })
+    }
+}
+
+impl RpcSimulation {
+    /// Pairs the accounts after the simulation with their state `before` it, both in the
+    /// order of `pubkeys`.
+    pub(crate) fn report(
+        self,
+        pubkeys: &[Pubkey],
+        before: Vec<Option<AccountState>>,
+    ) -> SimulationReport {
+        let accounts = match self.accounts {
+            Some(after) if after.len() == pubkeys.len() => pubkeys
+                .iter()
+                .zip(before.into_iter().chain(std::iter::repeat(None)))
+                .zip(after)
+                .map(|((pubkey, before), after)| AccountChange {
+                    pubkey: *pubkey,
+                    before,
+                    after: after.as_ref().and_then(RpcAccount::state),
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        SimulationReport {
+            error: self.err,
+            logs: self.logs.unwrap_or_default(),
+            units_consumed: self.units_consumed,
+            accounts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use solana_sdk::instruction::InstructionError;
+
+    use super::*;
+
+    fn account(lamports: u64, owner: &Pubkey) -> serde_json::Value {
+        json!({
+            "lamports": lamports,
+            "owner": owner.to_string(),
+            "data": [STANDARD.encode([1, 2]), "base64"],
+            "executable": false,
+        })
+    }
+
+    #[test]
+    fn reports_the_accounts_before_and_after_the_simulation() {
+        let (payer, created, owner) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let simulation: RpcSimulation = serde_json::from_value(json!({
+            "err": null,
+            "logs": ["Program log: hello"],
+            "accounts": [account(90, &owner), account(10, &owner)],
+            "unitsConsumed": 150,
+        }))
+        .unwrap();
+        let before = AccountState {
+            lamports: 100,
+            owner,
+            data: vec![1, 2],
+            executable: false,
+        };
+
+        let report = simulation.report(&[payer, created], vec![Some(before.clone()), None]);
+
+        assert!(report.is_success());
+        assert_eq!(report.logs, vec!["Program log: hello".to_string()]);
+        assert_eq!(report.units_consumed, Some(150));
+        assert_eq!(report.accounts[0].before, Some(before));
+        assert_eq!(report.accounts[0].lamports_delta(), -10);
+        assert_eq!(report.accounts[1].pubkey, created);
+        assert_eq!(report.accounts[1].lamports_delta(), 10);
+    }
+
+    #[test]
+    fn reports_why_the_transaction_would_fail() {
+        let simulation: RpcSimulation = serde_json::from_value(json!({
+            "err": { "InstructionError": [0, { "Custom": 1 }] },
+            "logs": null,
+            "accounts": null,
+            "unitsConsumed": null,
+        }))
+        .unwrap();
+
+        let report = simulation.report(&[Pubkey::new_unique()], Vec::new());
+
+        assert_eq!(
+            report.error,
+            Some(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(1)
+            ))
+        );
+        assert!(!report.is_success());
+        assert!(report.logs.is_empty());
+        assert!(report.accounts.is_empty());
+    }
+}
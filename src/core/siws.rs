@@ -0,0 +1,552 @@
+//! Sign In With Solana (SIWS) message text, as defined by the Wallet Standard `solana:signIn`
+//! feature.
+//!
+//! The message can be parsed back, which the adapter uses to check what a wallet signed. With
+//! the `verify` feature, this module also verifies sign ins on a server. That part needs neither
+//! a browser nor wasm, so the frontend and the backend share one implementation of the format.
+
+#[cfg(feature = "verify")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "verify")]
+use std::time::SystemTime;
+
+use crate::core::response::SignInInput;
+#[cfg(feature = "verify")]
+use crate::core::{error::SignInVerificationError, response::SignInOutput};
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Solana account:";
+
+/// Builds the SIWS message text that is signed for `input`, in the same format wallets with
+/// native `signIn` produce.
+///
+/// Returns `None` if `domain` or `address` is missing, as the message can't be built without
+/// them.
+pub fn create_sign_in_message(input: &SignInInput) -> Option<String> {
+    let domain = non_empty(&input.domain)?;
+    let address = non_empty(&input.address)?;
+
+    let mut message = format!("{}{}\n{}", domain, HEADER_SUFFIX, address);
+
+    if let Some(statement) = non_empty(&input.statement) {
+        message.push_str("\n\n");
+        message.push_str(statement);
+    }
+
+    let mut fields = Vec::new();
+    for (label, value) in fields_of(input) {
+        if let Some(value) = non_empty(value) {
+            fields.push(format!("{}: {}", label, value));
+        }
+    }
+    if let Some(resources) = &input.resources {
+        fields.push("Resources:".to_string());
+        fields.extend(resources.iter().map(|resource| format!("- {}", resource)));
+    }
+
+    if !fields.is_empty() {
+        message.push_str("\n\n");
+        message.push_str(&fields.join("\n"));
+    }
+
+    Some(message)
+}
+
+fn fields_of(input: &SignInInput) -> [(&'static str, &Option<String>); 8] {
+    [
+        ("URI", &input.uri),
+        ("Version", &input.version),
+        ("Chain ID", &input.chain_id),
+        ("Nonce", &input.nonce),
+        ("Issued At", &input.issued_at),
+        ("Expiration Time", &input.expiration_time),
+        ("Not Before", &input.not_before),
+        ("Request ID", &input.request_id),
+    ]
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().filter(|value| !value.is_empty())
+}
+
+/// Parses SIWS message text back into the fields it was built from.
+///
+/// Only the canonical text produced by [`create_sign_in_message`] is accepted, so a message
+/// parses if and only if building it again gives the same bytes.
+pub fn parse_sign_in_message(message: &str) -> Option<SignInInput> {
+    let (header, rest) = message.split_once('\n')?;
+    let domain = header.strip_suffix(HEADER_SUFFIX)?;
+    let (address, rest) = rest.split_once("\n\n").unwrap_or((rest, ""));
+
+    let mut input = SignInInput {
+        domain: Some(domain.to_string()),
+        address: Some(address.to_string()),
+        ..Default::default()
+    };
+
+    let (statement, fields) = match rest.split_once("\n\n") {
+        Some((statement, fields)) => (Some(statement), fields),
+        None if is_field(rest) => (None, rest),
+        None => (Some(rest).filter(|rest| !rest.is_empty()), ""),
+    };
+    // The statement is a single line, so fields can't be smuggled into it.
+    if statement.is_some_and(|statement| statement.contains('\n')) {
+        return None;
+    }
+    input.statement = statement.map(str::to_string);
+
+    let mut lines = fields.lines().filter(|line| !line.is_empty()).peekable();
+    while let Some(line) = lines.next() {
+        if line == "Resources:" {
+            let resources = input.resources.get_or_insert_with(Vec::new);
+            while let Some(resource) = lines.next_if(|line| line.starts_with("- ")) {
+                resources.extend(resource.strip_prefix("- ").map(str::to_string));
+            }
+            continue;
+        }
+
+        let (label, value) = line.split_once(": ")?;
+        let field = match label {
+            "URI" => &mut input.uri,
+            "Version" => &mut input.version,
+            "Chain ID" => &mut input.chain_id,
+            "Nonce" => &mut input.nonce,
+            "Issued At" => &mut input.issued_at,
+            "Expiration Time" => &mut input.expiration_time,
+            "Not Before" => &mut input.not_before,
+            "Request ID" => &mut input.request_id,
+            _ => return None,
+        };
+        *field = Some(value.to_string());
+    }
+
+    (create_sign_in_message(&input).as_deref() == Some(message)).then_some(input)
+}
+
+fn is_field(line: &str) -> bool {
+    line.starts_with("Resources:")
+        || [
+            "URI",
+            "Version",
+            "Chain ID",
+            "Nonce",
+            "Issued At",
+            "Expiration Time",
+            "Not Before",
+            "Request ID",
+        ]
+        .iter()
+        .any(|label| {
+            line.strip_prefix(label)
+                .is_some_and(|rest| rest.starts_with(": "))
+        })
+}
+
+/// The first field set in `input` that `signed` has a different value for, if any.
+#[cfg(feature = "web")]
+pub(crate) fn mismatched_field(input: &SignInInput, signed: &SignInInput) -> Option<&'static str> {
+    let differs = |expected: &Option<String>, actual: &Option<String>| {
+        expected.is_some() && actual != expected
+    };
+
+    if differs(&input.domain, &signed.domain) {
+        return Some("domain");
+    }
+    if differs(&input.address, &signed.address) {
+        return Some("address");
+    }
+    if differs(&input.statement, &signed.statement) {
+        return Some("statement");
+    }
+    if input.resources.is_some() && signed.resources != input.resources {
+        return Some("resources");
+    }
+    fields_of(input)
+        .into_iter()
+        .zip(fields_of(signed))
+        .find(|((_, expected), (_, actual))| differs(expected, actual))
+        .map(|((label, _), _)| label)
+}
+
+/// Verifies a sign in on the server against the `input` that was handed to the frontend.
+///
+/// Checks the ed25519 signature over the signed message, that the message is the canonical
+/// SIWS text for the signing account, that its domain and nonce are the expected ones (both
+/// are required in `input`), that every other field set in `input`, such as the chain, matches,
+/// and that the current time is within its validity window. Returns the parsed message.
+#[cfg(feature = "verify")]
+pub fn verify_sign_in(
+    input: &SignInInput,
+    output: &SignInOutput,
+) -> Result<SignInInput, SignInVerificationError> {
+    verify_sign_in_at(input, output, SystemTime::now().into())
+}
+
+/// Same as [`verify_sign_in`], checking expiry against `now`.
+#[cfg(feature = "verify")]
+pub fn verify_sign_in_at(
+    input: &SignInInput,
+    output: &SignInOutput,
+    now: DateTime<Utc>,
+) -> Result<SignInInput, SignInVerificationError> {
+    if !output
+        .signature
+        .verify(output.account.as_ref(), &output.signed_message)
+    {
+        return Err(SignInVerificationError::InvalidSignature);
+    }
+
+    let message = std::str::from_utf8(&output.signed_message)
+        .map_err(|_| SignInVerificationError::MalformedMessage)?;
+    let signed = parse_sign_in_message(message).ok_or(SignInVerificationError::MalformedMessage)?;
+
+    if signed.address != Some(output.account.to_string()) {
+        return Err(SignInVerificationError::AccountMismatch);
+    }
+    if input.domain.is_none() || signed.domain != input.domain {
+        return Err(SignInVerificationError::DomainMismatch);
+    }
+    if input.nonce.is_none() || signed.nonce != input.nonce {
+        return Err(SignInVerificationError::NonceMismatch);
+    }
+    if input.chain_id.is_some() && signed.chain_id != input.chain_id {
+        return Err(SignInVerificationError::ChainMismatch);
+    }
+    if input.address.is_some() && signed.address != input.address {
+        return Err(SignInVerificationError::AccountMismatch);
+    }
+    if input.statement.is_some() && signed.statement != input.statement {
+        return Err(SignInVerificationError::FieldMismatch("statement"));
+    }
+    if input.resources.is_some() && signed.resources != input.resources {
+        return Err(SignInVerificationError::FieldMismatch("resources"));
+    }
+    for ((label, expected), (_, actual)) in fields_of(input).into_iter().zip(fields_of(&signed)) {
+        if expected.is_some() && actual != expected {
+            return Err(SignInVerificationError::FieldMismatch(label));
+        }
+    }
+
+    let time = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(|value| {
+                DateTime::parse_from_rfc3339(value)
+                    .map(|time| time.with_timezone(&Utc))
+                    .map_err(|_| SignInVerificationError::MalformedMessage)
+            })
+            .transpose()
+    };
+    if time(&signed.expiration_time)?.is_some_and(|expiration_time| now >= expiration_time) {
+        return Err(SignInVerificationError::Expired);
+    }
+    if time(&signed.not_before)?.is_some_and(|not_before| now < not_before) {
+        return Err(SignInVerificationError::NotYetValid);
+    }
+    time(&signed.issued_at)?;
+
+    Ok(signed)
+}
+
+#[cfg(all(test, feature = "verify"))]
+mod tests {
+    use solana_sdk::signer::{keypair::Keypair, Signer};
+
+    use super::*;
+
+    fn full_input(keypair: &Keypair) -> SignInInput {
+        SignInInput {
+            domain: Some("example.com".to_string()),
+            address: Some(keypair.pubkey().to_string()),
+            statement: Some("Sign in to Example".to_string()),
+            uri: Some("https://example.com/login".to_string()),
+            version: Some("1".to_string()),
+            chain_id: Some("mainnet".to_string()),
+            nonce: Some("32891756".to_string()),
+            issued_at: Some("2024-01-01T00:00:00Z".to_string()),
+            expiration_time: Some("2024-01-02T00:00:00Z".to_string()),
+            not_before: Some("2023-12-31T00:00:00Z".to_string()),
+            request_id: Some("request-1".to_string()),
+            resources: Some(vec![
+                "ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq".to_string(),
+                "https://example.com/my-web2-claim.json".to_string(),
+            ]),
+        }
+    }
+
+    fn sign(keypair: &Keypair, message: &str) -> SignInOutput {
+        SignInOutput {
+            account: keypair.pubkey(),
+            signed_message: message.as_bytes().to_vec(),
+            signature: keypair.sign_message(message.as_bytes()),
+        }
+    }
+
+    fn at(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn roundtrips_every_field() {
+        let keypair = Keypair::new();
+        let input = full_input(&keypair);
+        let message = create_sign_in_message(&input).unwrap();
+
+        assert_eq!(
+            message,
+            format!(
+                "example.com wants you to sign in with your Solana account:\n\
+                 {}\n\
+                 \n\
+                 Sign in to Example\n\
+                 \n\
+                 URI: https://example.com/login\n\
+                 Version: 1\n\
+                 Chain ID: mainnet\n\
+                 Nonce: 32891756\n\
+                 Issued At: 2024-01-01T00:00:00Z\n\
+                 Expiration Time: 2024-01-02T00:00:00Z\n\
+                 Not Before: 2023-12-31T00:00:00Z\n\
+                 Request ID: request-1\n\
+                 Resources:\n\
+                 - ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq\n\
+                 - https://example.com/my-web2-claim.json",
+                keypair.pubkey()
+            )
+        );
+        assert_eq!(parse_sign_in_message(&message), Some(input.clone()));
+
+        // Each optional field on its own, and none at all.
+        let minimal = SignInInput {
+            domain: input.domain.clone(),
+            address: input.address.clone(),
+            ..Default::default()
+        };
+        let mut variants = vec![minimal.clone()];
+        variants.push(SignInInput {
+            statement: input.statement.clone(),
+            ..minimal.clone()
+        });
+        variants.push(SignInInput {
+            resources: input.resources.clone(),
+            ..minimal.clone()
+        });
+        for (label, _) in fields_of(&input) {
+            let mut variant = minimal.clone();
+            let field = match label {
+                "URI" => &mut variant.uri,
+                "Version" => &mut variant.version,
+                "Chain ID" => &mut variant.chain_id,
+                "Nonce" => &mut variant.nonce,
+                "Issued At" => &mut variant.issued_at,
+                "Expiration Time" => &mut variant.expiration_time,
+                "Not Before" => &mut variant.not_before,
+                _ => &mut variant.request_id,
+            };
+            *field = Some(format!("{} value", label));
+            variants.push(variant);
+        }
+
+        for variant in variants {
+            let message = create_sign_in_message(&variant).unwrap();
+            assert_eq!(
+                parse_sign_in_message(&message),
+                Some(variant),
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn requires_domain_and_address_to_build() {
+        let input = full_input(&Keypair::new());
+        assert_eq!(
+            create_sign_in_message(&SignInInput {
+                domain: None,
+                ..input.clone()
+            }),
+            None
+        );
+        assert_eq!(
+            create_sign_in_message(&SignInInput {
+                address: Some(String::new()),
+                ..input
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn rejects_non_canonical_and_garbled_text() {
+        let keypair = Keypair::new();
+        let message = create_sign_in_message(&full_input(&keypair)).unwrap();
+
+        let garbled = [
+            String::new(),
+            "not a sign in message".to_string(),
+            message.replace(HEADER_SUFFIX, " wants you to sign in:"),
+            message.replace("Nonce: ", "Nonce:"),
+            message.replace("Nonce", "Salt"),
+            message.replace("\n\nURI", "\nURI"),
+            message.replace(
+                "Version: 1\nChain ID: mainnet",
+                "Chain ID: mainnet\nVersion: 1",
+            ),
+            message.replace("- https", "* https"),
+            message.replace('\n', "\r\n"),
+            format!("{}\n", message),
+            message.replacen("\n\n", "\n\n\n", 1),
+        ];
+        for text in garbled {
+            assert_eq!(parse_sign_in_message(&text), None, "{:?}", text);
+        }
+
+        let input = full_input(&keypair);
+        let output = sign(&keypair, &message.replace("Nonce: ", "Nonce:"));
+        assert_eq!(
+            verify_sign_in_at(&input, &output, at("2024-01-01T12:00:00Z")),
+            Err(SignInVerificationError::MalformedMessage)
+        );
+
+        let invalid_utf8 = vec![0xff, 0xfe];
+        let output = SignInOutput {
+            account: keypair.pubkey(),
+            signature: keypair.sign_message(&invalid_utf8),
+            signed_message: invalid_utf8,
+        };
+        assert_eq!(
+            verify_sign_in_at(&input, &output, at("2024-01-01T12:00:00Z")),
+            Err(SignInVerificationError::MalformedMessage)
+        );
+    }
+
+    #[test]
+    fn verifies_a_valid_sign_in() {
+        let keypair = Keypair::new();
+        let input = full_input(&keypair);
+        let output = sign(&keypair, &create_sign_in_message(&input).unwrap());
+
+        assert_eq!(
+            verify_sign_in_at(&input, &output, at("2024-01-01T12:00:00Z")),
+            Ok(input)
+        );
+    }
+
+    #[test]
+    fn rejects_a_bad_signature() {
+        let keypair = Keypair::new();
+        let input = full_input(&keypair);
+        let message = create_sign_in_message(&input).unwrap();
+        let now = at("2024-01-01T12:00:00Z");
+
+        let signed_by_other = SignInOutput {
+            signature: Keypair::new().sign_message(message.as_bytes()),
+            ..sign(&keypair, &message)
+        };
+        assert_eq!(
+            verify_sign_in_at(&input, &signed_by_other, now),
+            Err(SignInVerificationError::InvalidSignature)
+        );
+
+        let mut tampered = sign(&keypair, &message);
+        tampered.signed_message = message.replace("32891756", "32891757").into_bytes();
+        assert_eq!(
+            verify_sign_in_at(&input, &tampered, now),
+            Err(SignInVerificationError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn enforces_domain_nonce_and_chain() {
+        let keypair = Keypair::new();
+        let input = full_input(&keypair);
+        let output = sign(&keypair, &create_sign_in_message(&input).unwrap());
+        let now = at("2024-01-01T12:00:00Z");
+
+        let cases = [
+            (
+                SignInInput {
+                    domain: Some("evil.com".to_string()),
+                    ..input.clone()
+                },
+                SignInVerificationError::DomainMismatch,
+            ),
+            (
+                SignInInput {
+                    domain: None,
+                    ..input.clone()
+                },
+                SignInVerificationError::DomainMismatch,
+            ),
+            (
+                SignInInput {
+                    nonce: Some("00000000".to_string()),
+                    ..input.clone()
+                },
+                SignInVerificationError::NonceMismatch,
+            ),
+            (
+                SignInInput {
+                    nonce: None,
+                    ..input.clone()
+                },
+                SignInVerificationError::NonceMismatch,
+            ),
+            (
+                SignInInput {
+                    chain_id: Some("devnet".to_string()),
+                    ..input.clone()
+                },
+                SignInVerificationError::ChainMismatch,
+            ),
+        ];
+        for (expected, error) in cases {
+            assert_eq!(verify_sign_in_at(&expected, &output, now), Err(error));
+        }
+
+        // A message signed for another account than the one returned.
+        let other = Keypair::new();
+        let output = sign(
+            &other,
+            &create_sign_in_message(&SignInInput {
+                address: Some(keypair.pubkey().to_string()),
+                ..input.clone()
+            })
+            .unwrap(),
+        );
+        assert_eq!(
+            verify_sign_in_at(&input, &output, now),
+            Err(SignInVerificationError::AccountMismatch)
+        );
+    }
+
+    #[test]
+    fn enforces_expiration_time_and_not_before() {
+        let keypair = Keypair::new();
+        let input = full_input(&keypair);
+        let output = sign(&keypair, &create_sign_in_message(&input).unwrap());
+
+        assert!(verify_sign_in_at(&input, &output, at("2023-12-31T00:00:00Z")).is_ok());
+        assert_eq!(
+            verify_sign_in_at(&input, &output, at("2023-12-30T23:59:59Z")),
+            Err(SignInVerificationError::NotYetValid)
+        );
+        assert!(verify_sign_in_at(&input, &output, at("2024-01-01T23:59:59Z")).is_ok());
+        assert_eq!(
+            verify_sign_in_at(&input, &output, at("2024-01-02T00:00:00Z")),
+            Err(SignInVerificationError::Expired)
+        );
+
+        let invalid_time = SignInInput {
+            expiration_time: Some("tomorrow".to_string()),
+            ..input
+        };
+        let output = sign(&keypair, &create_sign_in_message(&invalid_time).unwrap());
+        assert_eq!(
+            verify_sign_in_at(&invalid_time, &output, at("2024-01-01T12:00:00Z")),
+            Err(SignInVerificationError::MalformedMessage)
+        );
+    }
+}
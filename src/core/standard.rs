@@ -0,0 +1,475 @@
+use std::{
+    cell::{Cell, RefCell},
+    str::FromStr,
+};
+
+use js_sys::{Array, Function, Object, Promise, Reflect, Uint8Array};
+use log::info;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{window, CustomEvent, CustomEventInit, Event};
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::{
+    adapter::standard::{
+        SolanaSignAndSendTransactionFeature, SolanaSignInFeature, SolanaSignMessageFeature,
+        SolanaSignTransactionFeature, StandardConnectFeature, StandardDisconnectFeature,
+        StandardEventsFeature, StandardWallet, StandardWalletAccount, APP_READY_EVENT,
+        REGISTER_WALLET_EVENT, SOLANA_SIGN_AND_SEND_TRANSACTION, SOLANA_SIGN_IN,
+        SOLANA_SIGN_MESSAGE, SOLANA_SIGN_TRANSACTION, STANDARD_CONNECT, STANDARD_DISCONNECT,
+        STANDARD_EVENTS,
+    },
+    core::{
+        error::{WalletError, WalletOperation},
+        response::SignMessageOutput,
+        wallet::{BaseWalletAdapter, Wallet},
+    },
+};
+
+thread_local! {
+    static INITIALIZED: Cell<bool> = const { Cell::new(false) };
+    static REGISTRY: RefCell<Vec<StandardWallet>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns an adapter for every Wallet Standard compliant wallet that has registered itself
+/// so far.
+///
+/// Phantom, Solflare and Backpack register themselves too, under the names of their built-in
+/// adapters. They are left out, so a list of both has every wallet once.
+///
+/// The first call joins the `wallet-standard:app-ready` / `wallet-standard:register-wallet`
+/// handshake, so wallets that inject later are picked up by subsequent calls.
+pub fn get_wallets() -> Vec<BaseWalletAdapter> {
+    initialize();
+
+    wallet_names()
+        .into_iter()
+        .filter(|name| !is_builtin(name))
+        .map(|name| Wallet::Standard(name).into())
+        .collect()
+}
+
+/// Whether `name` is the name of a wallet with a built-in adapter.
+fn is_builtin(name: &str) -> bool {
+    [Wallet::Phantom, Wallet::Solflare, Wallet::Backpack]
+        .iter()
+        .any(|wallet| format!("{:?}", wallet) == name)
+}
+
+pub(crate) fn wallet_names() -> Vec<String> {
+    REGISTRY.with(|registry| registry.borrow().iter().map(wallet_name).collect())
+}
+
+pub(crate) fn find_wallet(name: &str) -> Option<StandardWallet> {
+    initialize();
+
+    REGISTRY.with(|registry| {
+        registry
+            .borrow()
+            .iter()
+            .find(|wallet| wallet_name(wallet) == name)
+            .cloned()
+    })
+}
+
+pub(crate) fn wallet_name(wallet: &StandardWallet) -> String {
+    wallet.name().as_string().unwrap_or_default()
+}
+
+pub(crate) fn wallet_icon(wallet: &StandardWallet) -> String {
+    wallet.icon().as_string().unwrap_or_default()
+}
+
+pub(crate) fn wallet_features(wallet: &StandardWallet) -> Vec<String> {
+    match wallet.features().dyn_into::<Object>() {
+        Ok(features) => Object::keys(&features)
+            .iter()
+            .filter_map(|key| key.as_string())
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn has_feature(wallet: &StandardWallet, feature: &str) -> bool {
+    Reflect::has(&wallet.features(), &JsValue::from_str(feature)).unwrap_or(false)
+}
+
+fn feature<T: JsCast>(wallet: &StandardWallet, feature: &str) -> Option<T> {
+    Reflect::get(&wallet.features(), &JsValue::from_str(feature))
+        .ok()
+        .filter(|value| value.is_object())
+        .map(JsCast::unchecked_into)
+}
+
+fn is_compatible(wallet: &StandardWallet) -> bool {
+    has_feature(wallet, STANDARD_CONNECT)
+        && has_feature(wallet, STANDARD_EVENTS)
+        && (has_feature(wallet, SOLANA_SIGN_AND_SEND_TRANSACTION)
+            || has_feature(wallet, SOLANA_SIGN_TRANSACTION))
+}
+
+fn register(wallet: StandardWallet) {
+    if !is_compatible(&wallet) {
+        info!("Ignoring incompatible wallet: {}", wallet_name(&wallet));
+        return;
+    }
+
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        if registry
+            .iter()
+            .any(|registered| wallet_name(registered) == wallet_name(&wallet))
+        {
+            return;
+        }
+        info!("Registered wallet: {}", wallet_name(&wallet));
+        registry.push(wallet);
+    });
+}
+
+fn unregister(wallet: &StandardWallet) {
+    REGISTRY.with(|registry| {
+        registry
+            .borrow_mut()
+            .retain(|registered| !Object::is(registered, wallet));
+    });
+}
+
+/// Builds the `{ register }` object handed to wallets. Wallets register one wallet per call,
+/// which is what `registerWallet` from `@wallet-standard/wallet` does.
+fn register_api() -> Object {
+    let register = Closure::<dyn Fn(JsValue) -> JsValue>::new(|wallet: JsValue| {
+        let wallet: StandardWallet = wallet.unchecked_into();
+        register(wallet.clone());
+        Closure::once_into_js(move || unregister(&wallet))
+    });
+
+    let api = Object::new();
+    let _ = Reflect::set(
+        &api,
+        &JsValue::from_str("register"),
+        &register.into_js_value(),
+    );
+    api
+}
+
+fn initialize() {
+    if INITIALIZED.with(|initialized| initialized.replace(true)) {
+        return;
+    }
+
+    let Some(window) = window() else {
+        return;
+    };
+
+    let api = register_api();
+
+    let listener = {
+        let api = api.clone();
+        Closure::<dyn Fn(Event)>::new(move |event: Event| {
+            let callback = event
+                .dyn_ref::<CustomEvent>()
+                .map(CustomEvent::detail)
+                .and_then(|detail| detail.dyn_into::<Function>().ok());
+
+            if let Some(callback) = callback {
+                if let Err(err) = callback.call1(&JsValue::UNDEFINED, &api) {
+                    log::error!("Failed to register wallet: {:?}", err);
+                }
+            }
+        })
+    };
+    let _ = window
+        .add_event_listener_with_callback(REGISTER_WALLET_EVENT, listener.as_ref().unchecked_ref());
+    listener.forget();
+
+    let init = CustomEventInit::new();
+    init.set_detail(&api);
+    if let Ok(event) = CustomEvent::new_with_event_init_dict(APP_READY_EVENT, &init) {
+        let _ = window.dispatch_event(&event);
+    }
+}
+
+fn accounts(wallet: &StandardWallet) -> Vec<StandardWalletAccount> {
+    match wallet.accounts().dyn_into::<Array>() {
+        Ok(accounts) => accounts
+            .iter()
+            .filter(JsValue::is_object)
+            .map(JsCast::unchecked_into)
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn account(
+    wallet: &StandardWallet,
+    public_key: &Pubkey,
+) -> Result<StandardWalletAccount, WalletError> {
+    let address = public_key.to_string();
+
+    accounts(wallet)
+        .into_iter()
+        .find(|account| account.address().as_string().as_deref() == Some(address.as_str()))
+        .ok_or(WalletError::WalletAccountError)
+}
+
+fn account_public_key(account: &StandardWalletAccount) -> Result<Pubkey, WalletError> {
+    account
+        .address()
+        .as_string()
+        .and_then(|address| Pubkey::from_str(&address).ok())
+        .ok_or(WalletError::WalletPublicKeyError)
+}
+
+/// `chain` if `account` supports it.
+fn supported_chain(account: &StandardWalletAccount, chain: &str) -> Result<JsValue, WalletError> {
+    let supported = account
+        .chains()
+        .dyn_into::<Array>()
+        .unwrap_or_default()
+        .iter()
+        .any(|supported| supported.as_string().as_deref() == Some(chain));
+
+    if !supported {
+        log::error!("Wallet account does not support {}", chain);
+        return Err(WalletError::WalletChainError {
+            chain: chain.to_string(),
+        });
+    }
+    Ok(JsValue::from_str(chain))
+}
+
+fn input(entries: &[(&str, &JsValue)]) -> Object {
+    let input = Object::new();
+    for (key, value) in entries {
+        let _ = Reflect::set(&input, &JsValue::from_str(key), value);
+    }
+    input
+}
+
+async fn first_output(promise: Result<Promise, JsValue>) -> Result<JsValue, JsValue> {
+    let outputs = JsFuture::from(promise?).await?;
+
+    match outputs.dyn_into::<Array>() {
+        Ok(outputs) if outputs.length() > 0 => Ok(outputs.get(0)),
+        Ok(_) => Err(JsValue::from_str("wallet returned no outputs")),
+        Err(outputs) => Ok(outputs),
+    }
+}
+
+fn output_bytes(output: &JsValue, key: &str) -> Option<Vec<u8>> {
+    Reflect::get(output, &JsValue::from_str(key))
+        .ok()
+        .and_then(|value| value.dyn_into::<Uint8Array>().ok())
+        .map(|value| value.to_vec())
+}
+
+/// Connects to the wallet. A `silent` connect only succeeds if the user approved the site
+/// before, and never prompts.
+pub(crate) async fn connect(name: &str, silent: bool) -> Result<Pubkey, WalletError> {
+    let wallet = find_wallet(name).ok_or(WalletError::WalletNotReadyError)?;
+    let connect = feature::<StandardConnectFeature>(&wallet, STANDARD_CONNECT)
+        .ok_or(WalletError::WalletConfigError)?;
+
+    first_output(connect.connect(&input(&[("silent", &JsValue::from_bool(silent))])))
+        .await
+        .map_err(|err| {
+            log::error!("Failed to connect wallet: {:?}", err);
+            WalletError::from_js(WalletOperation::Connect, &err)
+        })?;
+
+    accounts(&wallet)
+        .iter()
+        .find_map(|account| account_public_key(account).ok())
+        .ok_or(WalletError::WalletAccountError)
+}
+
+/// The accounts the wallet currently exposes, in its order.
+pub(crate) fn public_keys(name: &str) -> Vec<Pubkey> {
+    find_wallet(name)
+        .map(|wallet| {
+            accounts(&wallet)
+                .iter()
+                .filter_map(|account| account_public_key(account).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Calls `listener` with the event's properties whenever the wallet's accounts, chains or
+/// features change. Returns the function that removes the listener.
+pub(crate) fn on_change(name: &str, listener: &JsValue) -> Result<Function, JsValue> {
+    let wallet = find_wallet(name).ok_or_else(|| JsValue::from_str("wallet is not registered"))?;
+    let events = feature::<StandardEventsFeature>(&wallet, STANDARD_EVENTS)
+        .ok_or_else(|| JsValue::from_str("wallet has no standard:events feature"))?;
+
+    events.on("change", listener)?.dyn_into::<Function>()
+}
+
+pub(crate) async fn disconnect(name: &str) -> Result<(), WalletError> {
+    let wallet = find_wallet(name).ok_or(WalletError::WalletNotReadyError)?;
+
+    if let Some(disconnect) = feature::<StandardDisconnectFeature>(&wallet, STANDARD_DISCONNECT) {
+        first_output(disconnect.disconnect()).await.map_err(|err| {
+            log::error!("Failed to disconnect wallet: {:?}", err);
+            WalletError::from_js(WalletOperation::Disconnect, &err)
+        })?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn sign_transaction(
+    name: &str,
+    public_key: &Pubkey,
+    chain: &str,
+    transaction: &[u8],
+) -> Result<Vec<u8>, WalletError> {
+    let wallet = find_wallet(name).ok_or(WalletError::WalletNotReadyError)?;
+    let sign_transaction =
+        feature::<SolanaSignTransactionFeature>(&wallet, SOLANA_SIGN_TRANSACTION)
+            .ok_or(WalletError::WalletConfigError)?;
+    let account = account(&wallet, public_key)?;
+    let chain = supported_chain(&account, chain)?;
+
+    let input = input(&[
+        ("account", account.as_ref()),
+        ("transaction", &Uint8Array::from(transaction).into()),
+        ("chain", &chain),
+    ]);
+
+    let output = first_output(sign_transaction.sign_transaction(&input))
+        .await
+        .map_err(|err| {
+            log::error!("Failed to sign transaction: {:?}", err);
+            WalletError::from_js(WalletOperation::SignTransaction, &err)
+        })?;
+
+    output_bytes(&output, "signedTransaction").ok_or(WalletError::WalletSignTransactionError)
+}
+
+/// Signs every transaction in one `solana:signTransaction` call, so the wallet prompts once.
+/// Outputs line up with `transactions`; a missing or malformed output is `None`.
+pub(crate) async fn sign_all_transactions(
+    name: &str,
+    public_key: &Pubkey,
+    chain: &str,
+    transactions: &[Vec<u8>],
+) -> Result<Vec<Option<Vec<u8>>>, WalletError> {
+    let wallet = find_wallet(name).ok_or(WalletError::WalletNotReadyError)?;
+    let sign_transaction =
+        feature::<SolanaSignTransactionFeature>(&wallet, SOLANA_SIGN_TRANSACTION)
+            .ok_or(WalletError::WalletConfigError)?;
+    let account = account(&wallet, public_key)?;
+    let chain = supported_chain(&account, chain)?;
+
+    let inputs: Array = transactions
+        .iter()
+        .map(|transaction| {
+            JsValue::from(input(&[
+                ("account", account.as_ref()),
+                ("transaction", &Uint8Array::from(&transaction[..]).into()),
+                ("chain", &chain),
+            ]))
+        })
+        .collect();
+
+    let outputs = Reflect::get(&sign_transaction, &JsValue::from_str("signTransaction"))
+        .and_then(|function| function.dyn_into::<Function>())
+        .and_then(|function| Reflect::apply(&function, &sign_transaction, &inputs))
+        .and_then(|promise| promise.dyn_into::<Promise>())
+        .map_err(|err| {
+            log::error!("Failed to sign transactions: {:?}", err);
+            WalletError::from_js(WalletOperation::SignAllTransactions, &err)
+        })?;
+
+    let outputs = JsFuture::from(outputs)
+        .await
+        .map_err(|err| {
+            log::error!("Failed to sign transactions: {:?}", err);
+            WalletError::from_js(WalletOperation::SignAllTransactions, &err)
+        })?
+        .dyn_into::<Array>()
+        .map_err(|_| WalletError::WalletSignTransactionError)?;
+
+    Ok((0..transactions.len() as u32)
+        .map(|index| output_bytes(&outputs.get(index), "signedTransaction"))
+        .collect())
+}
+
+pub(crate) async fn sign_and_send_transaction(
+    name: &str,
+    public_key: &Pubkey,
+    chain: &str,
+    transaction: &[u8],
+    options: &JsValue,
+) -> Result<Signature, WalletError> {
+    let wallet = find_wallet(name).ok_or(WalletError::WalletNotReadyError)?;
+    let sign_and_send_transaction =
+        feature::<SolanaSignAndSendTransactionFeature>(&wallet, SOLANA_SIGN_AND_SEND_TRANSACTION)
+            .ok_or(WalletError::WalletConfigError)?;
+    let account = account(&wallet, public_key)?;
+    let chain = supported_chain(&account, chain)?;
+
+    let input = input(&[
+        ("account", account.as_ref()),
+        ("transaction", &Uint8Array::from(transaction).into()),
+        ("chain", &chain),
+        ("options", options),
+    ]);
+
+    let output = first_output(sign_and_send_transaction.sign_and_send_transaction(&input))
+        .await
+        .map_err(|err| {
+            log::error!("Failed to sign and send transaction: {:?}", err);
+            WalletError::from_js(WalletOperation::SignAndSendTransaction, &err)
+        })?;
+
+    output_bytes(&output, "signature")
+        .and_then(|signature| Signature::try_from(signature.as_slice()).ok())
+        .filter(|signature| *signature != Signature::default())
+        .ok_or(WalletError::WalletSendTransactionError)
+}
+
+pub(crate) async fn sign_message(
+    name: &str,
+    public_key: &Pubkey,
+    message: &[u8],
+) -> Result<SignMessageOutput, WalletError> {
+    let wallet = find_wallet(name).ok_or(WalletError::WalletNotReadyError)?;
+    let sign_message = feature::<SolanaSignMessageFeature>(&wallet, SOLANA_SIGN_MESSAGE)
+        .ok_or(WalletError::WalletConfigError)?;
+    let account = account(&wallet, public_key)?;
+
+    let input = input(&[
+        ("account", account.as_ref()),
+        ("message", &Uint8Array::from(message).into()),
+    ]);
+
+    let output = first_output(sign_message.sign_message(&input))
+        .await
+        .map_err(|err| {
+            log::error!("Failed to sign message: {:?}", err);
+            WalletError::from_js(WalletOperation::SignMessage, &err)
+        })?;
+
+    let signature = output_bytes(&output, "signature")
+        .and_then(|signature| Signature::try_from(signature.as_slice()).ok())
+        .ok_or(WalletError::WalletSignMessageError)?;
+
+    // Wallets may wrap the message before signing it; `signedMessage` is what was signed.
+    Ok(SignMessageOutput {
+        signed_message: output_bytes(&output, "signedMessage").unwrap_or_else(|| message.to_vec()),
+        signature,
+    })
+}
+
+/// Calls `solana:signIn` with `input` and returns the wallet's first output as is.
+pub(crate) async fn sign_in(name: &str, input: &JsValue) -> Result<JsValue, JsValue> {
+    let wallet = find_wallet(name).ok_or_else(|| JsValue::from_str("wallet not found"))?;
+    let sign_in = feature::<SolanaSignInFeature>(&wallet, SOLANA_SIGN_IN)
+        .ok_or_else(|| JsValue::from_str("wallet has no solana:signIn feature"))?;
+
+    first_output(sign_in.sign_in(input)).await
+}
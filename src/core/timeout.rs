@@ -0,0 +1,156 @@
+//! Timeouts and cancellation for pending wallet requests.
+
+use std::{
+    future::Future,
+    pin::pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::future::{select, AbortHandle, Abortable, Either};
+use js_sys::{Function, Promise};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::core::error::WalletError;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = setTimeout, catch)]
+    fn set_timeout(handler: &Function, timeout: f64) -> Result<JsValue, JsValue>;
+}
+
+/// How long a wallet request may stay pending before it fails with
+/// [`WalletError::WalletTimeoutError`]. `None`, the default, waits for as long as the wallet
+/// takes.
+///
+/// Timeouts apply per step, not to a request as a whole: waiting for earlier requests to
+/// settle, each wallet prompt and each call to the RPC node get the full timeout each.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WalletTimeouts {
+    /// Connecting and disconnecting.
+    pub connect: Option<Duration>,
+    /// Signing transactions and messages, and signing in.
+    pub sign: Option<Duration>,
+    /// Signing and sending transactions, and sending them through the RPC node.
+    pub send: Option<Duration>,
+}
+
+/// Aborts the requests an adapter is waiting on, e.g. when the user navigates away.
+///
+/// The handle is shared by an adapter and its clones. Cancelled requests fail with
+/// [`WalletError::WalletCancelledError`], including those still queued behind another request;
+/// the wallet may still show its prompt, as wallets have no way to withdraw one.
+#[derive(Clone, Default)]
+pub struct CancelHandle {
+    pending: Arc<Mutex<Pending>>,
+}
+
+#[derive(Default)]
+struct Pending {
+    next_id: u64,
+    requests: Vec<(u64, AbortHandle)>,
+}
+
+impl CancelHandle {
+    /// Cancels every request pending right now. Later requests are not affected.
+    pub fn cancel(&self) {
+        if let Ok(mut pending) = self.pending.lock() {
+            for (_, request) in pending.requests.drain(..) {
+                request.abort();
+            }
+        }
+    }
+
+    /// Whether a request is pending.
+    pub fn is_pending(&self) -> bool {
+        self.pending
+            .lock()
+            .map(|pending| !pending.requests.is_empty())
+            .unwrap_or(false)
+    }
+
+    /// Runs `future` until it completes, `timeout` elapses or the request is cancelled.
+    pub(crate) async fn run<T>(
+        &self,
+        timeout: Option<Duration>,
+        future: impl Future<Output = T>,
+    ) -> Result<T, WalletError> {
+        let (abort, registration) = AbortHandle::new_pair();
+        let id = self.register(abort);
+        let future = pin!(Abortable::new(future, registration));
+
+        let result = match timeout {
+            Some(timeout) => match select(future, pin!(sleep(timeout))).await {
+                Either::Left((result, _)) => result.map_err(|_| WalletError::WalletCancelledError),
+                Either::Right(_) => Err(WalletError::WalletTimeoutError),
+            },
+            None => future.await.map_err(|_| WalletError::WalletCancelledError),
+        };
+
+        self.unregister(id);
+        result
+    }
+
+    fn register(&self, request: AbortHandle) -> u64 {
+        let Ok(mut pending) = self.pending.lock() else {
+            return 0;
+        };
+        pending.next_id += 1;
+        let id = pending.next_id;
+        pending.requests.push((id, request));
+        id
+    }
+
+    fn unregister(&self, id: u64) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.requests.retain(|(request, _)| *request != id);
+        }
+    }
+}
+
+impl PartialEq for CancelHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.pending, &other.pending)
+    }
+}
+
+/// Resolves after `duration`, or never if there is no `setTimeout` to call.
+pub(crate) async fn sleep(duration: Duration) {
+    let promise = Promise::new(&mut |resolve, _| {
+        let _ = set_timeout(&resolve, duration.as_millis() as f64);
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{
+        executor::block_on,
+        future::{join, pending},
+    };
+
+    use super::*;
+
+    #[test]
+    fn cancel_aborts_pending_requests() {
+        let cancel = CancelHandle::default();
+
+        let (result, _) = block_on(join(cancel.run(None, pending::<()>()), async {
+            assert!(cancel.is_pending());
+            cancel.cancel();
+        }));
+
+        assert_eq!(result, Err(WalletError::WalletCancelledError));
+        assert!(!cancel.is_pending());
+    }
+
+    #[test]
+    fn cancel_leaves_later_requests_alone() {
+        let cancel = CancelHandle::default();
+        cancel.cancel();
+
+        assert_eq!(block_on(cancel.run(None, async { 1 })), Ok(1));
+        assert!(!cancel.is_pending());
+    }
+}
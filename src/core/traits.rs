@@ -1,16 +1,25 @@
 #![allow(async_fn_in_trait)]
 
-use anyhow::Result;
-
-use solana_client_wasm::WasmClient as RpcClient;
-use solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::Transaction};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use std::sync::Arc;
 
 use crate::core::{
-    error::WalletError, transaction::TransactionOrVersionedTransaction, wallet::WalletReadyState,
+    client::WasmClient as RpcClient,
+    error::WalletError,
+    events::{EventStream, Subscription, WalletEvent},
+    response::{MessageDisplay, SignInInput, SignInOutput, SignMessageOutput},
+    transaction::{SendMode, SendOptions, TransactionOrVersionedTransaction},
+    wallet::WalletReadyState,
 };
 
 pub trait WalletAdapterEvents {
+    /// Calls `listener` with every event of the adapter and its clones until the returned
+    /// subscription is dropped.
+    fn subscribe(&self, listener: impl Fn(&WalletEvent) + 'static) -> Subscription;
+
+    /// The adapter's events as a stream, for code that awaits them.
+    fn events(&self) -> EventStream;
+
     fn emit_connect(&mut self, public_key: Pubkey);
     fn emit_disconnect(&mut self);
     fn emit_error(&mut self, error: WalletError);
@@ -22,6 +31,7 @@ pub trait WalletAdapter: WalletAdapterEvents + Send + Sync {
     fn name(&self) -> String;
     fn url(&self) -> String;
     fn icon(&self) -> String;
+    fn features(&self) -> Vec<String>;
     fn ready_state(&self) -> WalletReadyState;
     fn public_key(&self) -> Option<Pubkey>;
     fn connecting(&self) -> bool;
@@ -35,28 +45,95 @@ pub trait WalletAdapter: WalletAdapterEvents + Send + Sync {
         &mut self,
         client: Arc<RpcClient>,
         transaction: TransactionOrVersionedTransaction,
+    ) -> Result<Signature, WalletError> {
+        self.send_transaction_with_options(client, transaction, SendOptions::default())
+            .await
+    }
+
+    /// Sends a signed `transaction` through `client`, passing `options` on to the RPC node.
+    async fn send_transaction_with_options(
+        &mut self,
+        client: Arc<RpcClient>,
+        transaction: TransactionOrVersionedTransaction,
+        options: SendOptions,
     ) -> Result<Signature, WalletError>;
+}
+
+// The capabilities don't build on each other, as Wallet Standard wallets declare them one by
+// one. Code that needs several asks for each, e.g.
+// `W: SignerWalletAdapter + MessageSignerWalletAdapter`.
+
+/// A wallet that can sign transactions.
+pub trait SignerWalletAdapter: WalletAdapter {
+    /// Returns the transaction with the wallet's signature in its slot.
     async fn sign_transaction(
         &mut self,
-        transaction: Transaction,
-    ) -> Result<Signature, WalletError>;
+        transaction: impl Into<TransactionOrVersionedTransaction>,
+    ) -> Result<TransactionOrVersionedTransaction, WalletError>;
+
+    /// Signs a batch of transactions behind a single wallet prompt.
+    ///
+    /// Fails as a whole if the request is rejected; otherwise returns one result per
+    /// transaction, in the order they were given.
+    async fn sign_all_transactions(
+        &mut self,
+        transactions: impl IntoIterator<Item = impl Into<TransactionOrVersionedTransaction>>,
+    ) -> Result<Vec<Result<TransactionOrVersionedTransaction, WalletError>>, WalletError>;
+
     async fn sign_send_transaction(
         &mut self,
-        transaction: Transaction,
+        transaction: impl Into<TransactionOrVersionedTransaction>,
+    ) -> Result<Signature, WalletError> {
+        self.sign_send_transaction_with_options(transaction, SendOptions::default())
+            .await
+    }
+
+    /// Signs and submits `transaction`, passing `options` on to the wallet, or to the RPC node
+    /// when the wallet can only sign.
+    async fn sign_send_transaction_with_options(
+        &mut self,
+        transaction: impl Into<TransactionOrVersionedTransaction>,
+        options: SendOptions,
     ) -> Result<Signature, WalletError>;
-    async fn sign_message(&mut self, message: &str) -> Result<Signature, WalletError>;
-}
 
-pub trait SignerWalletAdapter: WalletAdapter {
-    async fn sign_transaction(&self, transaction: &mut Transaction) -> Result<()>;
+    /// Signs and submits a batch of transactions, returning a signature or an error for each.
+    ///
+    /// Uses the wallet's own batch submission when it has one; otherwise signs everything
+    /// behind a single prompt and sends each transaction through the adapter's connection.
+    async fn sign_send_all_transactions(
+        &mut self,
+        transactions: impl IntoIterator<Item = impl Into<TransactionOrVersionedTransaction>>,
+        mode: SendMode,
+    ) -> Result<Vec<Result<Signature, WalletError>>, WalletError> {
+        self.sign_send_all_transactions_with_options(transactions, mode, SendOptions::default())
+            .await
+    }
 
-    async fn sign_all_transactions(&self, transactions: &mut [Transaction]) -> Result<()>;
+    async fn sign_send_all_transactions_with_options(
+        &mut self,
+        transactions: impl IntoIterator<Item = impl Into<TransactionOrVersionedTransaction>>,
+        mode: SendMode,
+        options: SendOptions,
+    ) -> Result<Vec<Result<Signature, WalletError>>, WalletError>;
 }
 
-pub trait MessageSignerWalletAdapter: SignerWalletAdapter {
-    async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>>;
+/// A wallet that can sign arbitrary messages.
+pub trait MessageSignerWalletAdapter: WalletAdapter {
+    /// Signs the raw `message` bytes, shown to the user as text.
+    async fn sign_message(&mut self, message: &[u8]) -> Result<SignMessageOutput, WalletError> {
+        self.sign_message_with_display(message, MessageDisplay::default())
+            .await
+    }
+
+    async fn sign_message_with_display(
+        &mut self,
+        message: &[u8],
+        display: MessageDisplay,
+    ) -> Result<SignMessageOutput, WalletError>;
 }
 
-pub trait SignInMessageSignerWalletAdapter: MessageSignerWalletAdapter {
-    async fn sign_in(&self) -> Result<()>;
+/// A wallet that can Sign In With Solana, connecting and proving ownership of the account in a
+/// single prompt.
+pub trait SignInMessageSignerWalletAdapter: WalletAdapter {
+    async fn sign_in(&mut self, input: SignInInput) -> Result<SignInOutput, WalletError>;
 }
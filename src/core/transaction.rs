@@ -1,8 +1,404 @@
 use serde::{Deserialize, Serialize};
-use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use solana_sdk::{
+    commitment_config::CommitmentLevel,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::{Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{Transaction, VersionedTransaction},
+};
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TransactionOrVersionedTransaction {
     Transaction(Transaction),
     VersionedTransaction(VersionedTransaction),
 }
+
+impl TransactionOrVersionedTransaction {
+    /// Serializes the transaction into its wire format, as expected by wallets and RPC nodes.
+    pub fn to_bytes(&self) -> bincode::Result<Vec<u8>> {
+        match self {
+            TransactionOrVersionedTransaction::Transaction(tx) => bincode::serialize(tx),
+            TransactionOrVersionedTransaction::VersionedTransaction(vtx) => bincode::serialize(vtx),
+        }
+    }
+
+    /// Deserializes wire format `bytes` into the same kind of transaction as `self`.
+    pub fn from_bytes_like(&self, bytes: &[u8]) -> bincode::Result<Self> {
+        Ok(match self {
+            TransactionOrVersionedTransaction::Transaction(_) => {
+                TransactionOrVersionedTransaction::Transaction(bincode::deserialize(bytes)?)
+            }
+            TransactionOrVersionedTransaction::VersionedTransaction(_) => {
+                TransactionOrVersionedTransaction::VersionedTransaction(bincode::deserialize(
+                    bytes,
+                )?)
+            }
+        })
+    }
+
+    /// The serialized message, i.e. the bytes every signature is made over.
+    pub fn message_data(&self) -> Vec<u8> {
+        match self {
+            TransactionOrVersionedTransaction::Transaction(tx) => tx.message_data(),
+            TransactionOrVersionedTransaction::VersionedTransaction(vtx) => vtx.message.serialize(),
+        }
+    }
+
+    pub fn recent_blockhash(&self) -> &Hash {
+        match self {
+            TransactionOrVersionedTransaction::Transaction(tx) => &tx.message.recent_blockhash,
+            TransactionOrVersionedTransaction::VersionedTransaction(vtx) => {
+                vtx.message.recent_blockhash()
+            }
+        }
+    }
+
+    /// Whether the transaction was built without a recent blockhash.
+    pub fn needs_blockhash(&self) -> bool {
+        *self.recent_blockhash() == Hash::default()
+    }
+
+    /// Sets the recent blockhash if it is missing, and makes `fee_payer` pay for legacy messages
+    /// that have no signer at all. Signatures made over the previous message are cleared.
+    ///
+    /// Returns whether the message changed.
+    pub fn fill(&mut self, fee_payer: &Pubkey, blockhash: Option<Hash>) -> bool {
+        let blockhash = blockhash.filter(|_| self.needs_blockhash());
+
+        let changed = match self {
+            TransactionOrVersionedTransaction::Transaction(tx) => {
+                fill_legacy(&mut tx.message, fee_payer, blockhash)
+            }
+            TransactionOrVersionedTransaction::VersionedTransaction(vtx) => match vtx.message {
+                VersionedMessage::Legacy(ref mut message) => {
+                    fill_legacy(message, fee_payer, blockhash)
+                }
+                VersionedMessage::V0(ref mut message) => match blockhash {
+                    Some(blockhash) => {
+                        message.recent_blockhash = blockhash;
+                        true
+                    }
+                    None => false,
+                },
+            },
+        };
+
+        if changed {
+            let num_required_signatures = self.signer_keys().len();
+            let signatures = match self {
+                TransactionOrVersionedTransaction::Transaction(tx) => &mut tx.signatures,
+                TransactionOrVersionedTransaction::VersionedTransaction(vtx) => &mut vtx.signatures,
+            };
+            *signatures = vec![Signature::default(); num_required_signatures];
+        }
+        changed
+    }
+
+    pub fn signatures(&self) -> &[Signature] {
+        match self {
+            TransactionOrVersionedTransaction::Transaction(tx) => &tx.signatures,
+            TransactionOrVersionedTransaction::VersionedTransaction(vtx) => &vtx.signatures,
+        }
+    }
+
+    /// The accounts that must sign, in signature slot order.
+    pub fn signer_keys(&self) -> &[Pubkey] {
+        let (keys, num_required_signatures) = match self {
+            TransactionOrVersionedTransaction::Transaction(tx) => (
+                &tx.message.account_keys[..],
+                tx.message.header.num_required_signatures,
+            ),
+            TransactionOrVersionedTransaction::VersionedTransaction(vtx) => (
+                vtx.message.static_account_keys(),
+                vtx.message.header().num_required_signatures,
+            ),
+        };
+
+        &keys[..keys.len().min(num_required_signatures as usize)]
+    }
+
+    /// The accounts the transaction may write to. Accounts loaded from lookup tables are left
+    /// out.
+    pub fn writable_keys(&self) -> Vec<Pubkey> {
+        match self {
+            TransactionOrVersionedTransaction::Transaction(tx) => tx
+                .message
+                .account_keys
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| tx.message.is_writable(*index))
+                .map(|(_, key)| *key)
+                .collect(),
+            TransactionOrVersionedTransaction::VersionedTransaction(vtx) => vtx
+                .message
+                .static_account_keys()
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| vtx.message.is_maybe_writable(*index))
+                .map(|(_, key)| *key)
+                .collect(),
+        }
+    }
+
+    /// The signature slot of `public_key`, if it is one of the required signers.
+    pub fn signer_index(&self, public_key: &Pubkey) -> Option<usize> {
+        self.signer_keys().iter().position(|key| key == public_key)
+    }
+
+    /// Places `signature` in slot `index`, growing the signature list to the number of required
+    /// signers if needed. Returns `false` if `index` is not a signer slot.
+    pub fn set_signature(&mut self, index: usize, signature: Signature) -> bool {
+        let (signatures, num_required_signatures) = match self {
+            TransactionOrVersionedTransaction::Transaction(tx) => (
+                &mut tx.signatures,
+                tx.message.header.num_required_signatures,
+            ),
+            TransactionOrVersionedTransaction::VersionedTransaction(vtx) => (
+                &mut vtx.signatures,
+                vtx.message.header().num_required_signatures,
+            ),
+        };
+
+        let num_required_signatures = num_required_signatures as usize;
+        if index >= num_required_signatures {
+            return false;
+        }
+        if signatures.len() < num_required_signatures {
+            signatures.resize(num_required_signatures, Signature::default());
+        }
+        signatures[index] = signature;
+        true
+    }
+}
+
+fn fill_legacy(message: &mut Message, fee_payer: &Pubkey, blockhash: Option<Hash>) -> bool {
+    let mut changed = false;
+    if let Some(blockhash) = blockhash {
+        message.recent_blockhash = blockhash;
+        changed = true;
+    }
+    if message.header.num_required_signatures == 0 {
+        if let Some(instructions) = instructions(message) {
+            *message = Message::new_with_blockhash(
+                &instructions,
+                Some(fee_payer),
+                &message.recent_blockhash,
+            );
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// The instructions `message` was compiled from, or `None` if it points at missing accounts.
+fn instructions(message: &Message) -> Option<Vec<Instruction>> {
+    message
+        .instructions
+        .iter()
+        .map(|instruction| {
+            let key = |index: u8| message.account_keys.get(index as usize).copied();
+
+            Some(Instruction {
+                program_id: key(instruction.program_id_index)?,
+                accounts: instruction
+                    .accounts
+                    .iter()
+                    .map(|&index| {
+                        Some(AccountMeta {
+                            pubkey: key(index)?,
+                            is_signer: message.is_signer(index as usize),
+                            is_writable: message.is_writable(index as usize),
+                        })
+                    })
+                    .collect::<Option<_>>()?,
+                data: instruction.data.clone(),
+            })
+        })
+        .collect()
+}
+
+/// How the RPC node handles a submitted transaction. Wallets that submit transactions themselves
+/// get the same options.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendOptions {
+    /// Skips the checks the RPC node runs before forwarding the transaction.
+    pub skip_preflight: bool,
+    /// The commitment the preflight checks run at.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preflight_commitment: Option<CommitmentLevel>,
+    /// How many times the RPC node resends the transaction to the leader.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<usize>,
+    /// The minimum slot the RPC node has to be at to run the preflight checks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_context_slot: Option<u64>,
+}
+
+/// How a batch of signed transactions is submitted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SendMode {
+    /// Submit every transaction at once.
+    #[default]
+    Parallel,
+    /// Submit one transaction at a time, waiting for each to confirm. A failure stops the
+    /// transactions after it from being sent.
+    Sequential,
+}
+
+impl SendMode {
+    /// The `mode` option understood by Phantom's `signAndSendAllTransactions`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SendMode::Parallel => "parallel",
+            SendMode::Sequential => "serial",
+        }
+    }
+}
+
+impl From<Transaction> for TransactionOrVersionedTransaction {
+    fn from(tx: Transaction) -> Self {
+        TransactionOrVersionedTransaction::Transaction(tx)
+    }
+}
+
+impl From<VersionedTransaction> for TransactionOrVersionedTransaction {
+    fn from(vtx: VersionedTransaction) -> Self {
+        TransactionOrVersionedTransaction::VersionedTransaction(vtx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use solana_sdk::{message::v0, system_instruction};
+
+    use super::*;
+
+    /// A transfer from `from` with `to` as fee payer, so both have to sign.
+    fn transfer(from: &Pubkey, to: &Pubkey) -> Transaction {
+        let transfer = system_instruction::transfer(from, to, 1);
+        Transaction::new_with_payer(&[transfer], Some(to))
+    }
+
+    #[test]
+    fn signatures_go_in_the_slot_of_their_signer() {
+        let (from, payer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut transaction = TransactionOrVersionedTransaction::from(transfer(&from, &payer));
+        assert_eq!(transaction.signer_keys(), &[payer, from]);
+
+        let index = transaction.signer_index(&from).unwrap();
+        assert!(transaction.set_signature(index, Signature::from([7; 64])));
+        assert_eq!(
+            transaction.signatures(),
+            &[Signature::default(), Signature::from([7; 64])]
+        );
+
+        assert!(!transaction.set_signature(2, Signature::from([7; 64])));
+        assert_eq!(transaction.signer_index(&Pubkey::new_unique()), None);
+    }
+
+    #[test]
+    fn wire_format_round_trips_into_the_same_kind_of_transaction() {
+        let legacy = transfer(&Pubkey::new_unique(), &Pubkey::new_unique());
+        let versioned = VersionedTransaction::from(legacy.clone());
+
+        let transactions: [TransactionOrVersionedTransaction; 2] =
+            [legacy.into(), versioned.into()];
+        for transaction in transactions {
+            let bytes = transaction.to_bytes().unwrap();
+            assert_eq!(transaction.from_bytes_like(&bytes).unwrap(), transaction);
+        }
+        assert!(
+            TransactionOrVersionedTransaction::from(Transaction::default())
+                .from_bytes_like(&[1, 2, 3])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn fill_sets_only_a_missing_blockhash() {
+        let (from, payer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let blockhash = Hash::new_unique();
+        let mut transaction = TransactionOrVersionedTransaction::from(transfer(&from, &payer));
+        transaction.set_signature(0, Signature::from([7; 64]));
+        assert!(transaction.needs_blockhash());
+
+        assert!(transaction.fill(&from, Some(blockhash)));
+        assert_eq!(*transaction.recent_blockhash(), blockhash);
+        // The signature was made over the message without the blockhash.
+        assert_eq!(transaction.signatures(), &[Signature::default(); 2]);
+        // The payer the transaction was built with is kept.
+        assert_eq!(transaction.signer_keys(), &[payer, from]);
+
+        assert!(!transaction.fill(&from, Some(Hash::new_unique())));
+        assert_eq!(*transaction.recent_blockhash(), blockhash);
+    }
+
+    #[test]
+    fn fill_makes_the_fee_payer_pay_for_messages_without_signers() {
+        let (account, fee_payer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1],
+            vec![AccountMeta::new(account, false)],
+        );
+        let mut transaction = TransactionOrVersionedTransaction::from(Transaction::new_unsigned(
+            Message::new(&[instruction], None),
+        ));
+        assert!(transaction.signer_keys().is_empty());
+
+        assert!(transaction.fill(&fee_payer, None));
+        assert_eq!(transaction.signer_keys(), &[fee_payer]);
+        assert_eq!(transaction.signatures(), &[Signature::default()]);
+        assert!(transaction.writable_keys().contains(&account));
+        assert!(transaction.needs_blockhash());
+    }
+
+    #[test]
+    fn fill_sets_the_blockhash_of_v0_messages() {
+        let payer = Pubkey::new_unique();
+        let transfer = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+        let message = v0::Message::try_compile(&payer, &[transfer], &[], Hash::default()).unwrap();
+        let mut transaction = TransactionOrVersionedTransaction::from(VersionedTransaction {
+            signatures: Vec::new(),
+            message: VersionedMessage::V0(message),
+        });
+        let blockhash = Hash::new_unique();
+
+        assert!(!transaction.fill(&payer, None));
+        assert!(transaction.fill(&payer, Some(blockhash)));
+        assert_eq!(*transaction.recent_blockhash(), blockhash);
+        assert_eq!(transaction.signatures(), &[Signature::default()]);
+    }
+
+    #[test]
+    fn default_send_options_only_set_skip_preflight() {
+        assert_eq!(
+            serde_json::to_value(SendOptions::default()).unwrap(),
+            json!({ "skipPreflight": false })
+        );
+    }
+
+    #[test]
+    fn send_options_use_the_wallet_standard_names() {
+        let options = SendOptions {
+            skip_preflight: true,
+            preflight_commitment: Some(CommitmentLevel::Processed),
+            max_retries: Some(3),
+            min_context_slot: Some(42),
+        };
+
+        assert_eq!(
+            serde_json::to_value(options).unwrap(),
+            json!({
+                "skipPreflight": true,
+                "preflightCommitment": "processed",
+                "maxRetries": 3,
+                "minContextSlot": 42,
+            })
+        );
+    }
+}
@@ -1,40 +1,69 @@
-#![allow(deprecated)]
-
 use std::str::FromStr;
 
 use anyhow::Result;
-use emitter_rs::EventEmitter;
-use js_sys::{Promise, Uint8Array};
+use futures::future::join_all;
+use js_sys::{Array, Function, Promise, Uint8Array};
 use log::info;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use wasm_bindgen::JsValue;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_futures::JsFuture;
 use web_sys::window;
 
-use solana_client_wasm::WasmClient as RpcClient;
-use solana_sdk::{bs58, pubkey::Pubkey, signature::Signature, transaction::Transaction};
+use solana_sdk::{
+    bs58, commitment_config::CommitmentConfig, hash::Hash, pubkey::Pubkey, signature::Signature,
+};
 
 use crate::{
     adapter::{
-        backpack::{BACKPACK, XNFT},
-        phantom::SOLANA,
-        solflare::SOLFLARE,
+        backpack::Backpack,
+        phantom::Solana,
+        solflare::Solflare,
+        standard::{SOLANA_SIGN_AND_SEND_TRANSACTION, SOLANA_SIGN_IN},
     },
     core::{
-        error::WalletError,
-        response::{JsSignatureObject, JsSignatureResponse, SignaturesObject},
+        client::{Cluster, WasmClient as RpcClient},
+        confirmation::{self, Confirmation, Expiry},
+        error::{WalletError, WalletOperation},
+        events::{EventStream, Subscription, WalletEvent, WalletEvents},
+        listeners::{self, ProviderEvent},
+        queue::{Request, RequestPolicy, RequestQueue},
+        rebroadcast::{self, RebroadcastConfig, RebroadcastReport, SendStrategy},
+        response::{
+            JsBytes, JsSignatureObject, JsSignatureResponse, JsSignaturesObject, MessageDisplay,
+            SignInInput, SignInOutput, SignMessageOutput, SignedTransactionResponse,
+            TransactionSignature,
+        },
+        simulation::{RpcSimulation, SimulationReport},
+        siws::{create_sign_in_message, mismatched_field, parse_sign_in_message},
+        standard,
+        timeout::{sleep, CancelHandle, WalletTimeouts},
         traits::{WalletAdapter, WalletAdapterEvents},
-        transaction::TransactionOrVersionedTransaction,
+        transaction::{SendMode, SendOptions, TransactionOrVersionedTransaction},
     },
 };
 
+/// How often [`BaseWalletAdapter::wait_for_ready_state_change`] checks for the wallet.
+const READY_STATE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How many blocks past its last valid block height a filled in blockhash is remembered.
+const BLOCKHASH_RETENTION: u64 = 300;
+
+/// How many sent transactions the adapter remembers the blockhash of, to tell when they expire.
+const SENT_RETENTION: usize = 100;
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub enum Wallet {
     #[default]
     Phantom,
     Solflare,
     Backpack,
+    /// A wallet discovered at runtime through the Wallet Standard, identified by its name.
+    Standard(String),
 }
 
 impl From<Wallet> for BaseWalletAdapter {
@@ -55,11 +84,26 @@ impl From<Wallet> for BaseWalletAdapter {
                 "https://backpack.app",
                 "images/backpack_logo.png",
             ),
+            Wallet::Standard(name) => {
+                let wallet = standard::find_wallet(&name);
+                let mut adapter = BaseWalletAdapter::new(
+                    Wallet::Standard(name),
+                    "",
+                    &wallet
+                        .as_ref()
+                        .map(standard::wallet_icon)
+                        .unwrap_or_default(),
+                );
+                if let Some(wallet) = wallet {
+                    adapter.features = standard::wallet_features(&wallet);
+                }
+                adapter
+            }
         }
     }
 }
 
-#[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WalletReadyState {
     Installed,
     #[default]
@@ -68,399 +112,2229 @@ pub enum WalletReadyState {
     Unsupported,
 }
 
+/// State shared by an adapter, its clones and the listeners on the wallet's own events.
+#[derive(Clone, Default)]
+struct Session(Arc<Mutex<SessionState>>);
+
+#[derive(Default)]
+struct SessionState {
+    public_key: Option<Pubkey>,
+    accounts: Vec<Pubkey>,
+    subscription: Option<u64>,
+    connecting: bool,
+    skip_signature_verification: bool,
+    timeouts: WalletTimeouts,
+    request_policy: RequestPolicy,
+    connection: Option<Arc<RpcClient>>,
+    cluster: Option<Cluster>,
+    blockhashes: HashMap<Hash, u64>,
+    sent: VecDeque<(Signature, Hash)>,
+    simulate_before_signing: bool,
+    send_strategy: SendStrategy,
+}
+
+impl Session {
+    fn with<T>(&self, f: impl FnOnce(&mut SessionState) -> T) -> T {
+        f(&mut self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn public_key(&self) -> Option<Pubkey> {
+        self.with(|state| state.public_key)
+    }
+
+    fn set_public_key(&self, public_key: Option<Pubkey>) {
+        self.with(|state| state.public_key = public_key);
+    }
+
+    fn accounts(&self) -> Vec<Pubkey> {
+        self.with(|state| state.accounts.clone())
+    }
+
+    fn clear(&self) {
+        self.with(|state| {
+            state.public_key = None;
+            state.accounts.clear();
+        });
+    }
+
+    /// Marks a connect as pending, unless one already is.
+    fn start_connecting(&self) -> bool {
+        self.with(|state| !std::mem::replace(&mut state.connecting, true))
+    }
+
+    /// Replaces the listeners on the wallet's events, removing the previous ones.
+    fn set_subscription(&self, subscription: Option<u64>) {
+        let previous = self.with(|state| std::mem::replace(&mut state.subscription, subscription));
+        if let Some(previous) = previous {
+            listeners::unsubscribe(previous);
+        }
+    }
+
+    fn disconnect(&self) {
+        self.clear();
+        self.set_subscription(None);
+    }
+
+    fn record_sent(&self, signature: Signature, blockhash: Hash) {
+        self.with(|state| {
+            if state.sent.len() == SENT_RETENTION {
+                state.sent.pop_front();
+            }
+            state.sent.push_back((signature, blockhash));
+        });
+    }
+
+    /// What tells that a transaction using `blockhash` expired.
+    fn expiry(&self, blockhash: Hash) -> Expiry {
+        self.with(|state| Expiry {
+            blockhash: Some(blockhash).filter(|blockhash| *blockhash != Hash::default()),
+            last_valid_block_height: state.blockhashes.get(&blockhash).copied(),
+        })
+    }
+}
+
+impl PartialEq for Session {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// A handle to a wallet. Clones are cheap and share the connection, settings, pending requests
+/// and listeners, so connecting through one clone connects them all.
+///
+/// Adapters are equal when they are handles to the same wallet, i.e. clones of one another.
 #[derive(Default, Clone, PartialEq)]
 pub struct BaseWalletAdapter {
     name: Wallet,
     url: String,
     icon: String,
-    ready_state: WalletReadyState,
-    public_key: Option<Pubkey>,
-    connecting: bool,
-    pub emitter: EventEmitter,
+    features: Vec<String>,
+    session: Session,
+    cancel: CancelHandle,
+    requests: RequestQueue,
+    events: WalletEvents,
 }
 
 impl BaseWalletAdapter {
     pub fn new(name: Wallet, url: &str, icon: &str) -> Self {
-        let ready_state = if cfg!(target_arch = "wasm32") {
-            WalletReadyState::Unsupported
-        } else {
-            WalletReadyState::NotDetected
-        };
-
         BaseWalletAdapter {
-            ready_state,
             name,
             url: url.to_string(),
             icon: icon.to_string(),
-            public_key: None,
-            connecting: false,
-            emitter: EventEmitter::new(),
+            features: Vec::new(),
+            session: Session::default(),
+            cancel: CancelHandle::default(),
+            requests: RequestQueue::default(),
+            events: WalletEvents::default(),
         }
     }
-}
 
-impl WalletAdapter for BaseWalletAdapter {
-    fn name(&self) -> String {
-        format!("{:?}", self.name).to_string()
+    /// Records a new connection and starts following the accounts the wallet reports.
+    async fn set_connected(&mut self, public_key: Pubkey) {
+        self.session.set_public_key(Some(public_key));
+        self.emit_connect(public_key);
+        if let Err(err) = self.refresh_accounts().await {
+            info!("Wallet did not list its accounts: {}", err);
+        }
+
+        // The wallet's own events update the session and are passed on to the listeners
+        // shared with the adapter's clones.
+        let session = self.session.clone();
+        let events = self.events.clone();
+        let subscription = listeners::subscribe(&self.name, move |event| match event {
+            ProviderEvent::Connect(Some(public_key))
+            | ProviderEvent::AccountChanged(Some(public_key)) => {
+                let previous = session.public_key();
+                if previous != Some(public_key) {
+                    info!("Wallet switched to {}", public_key);
+                    if previous.is_none() {
+                        session.set_public_key(Some(public_key));
+                        events.emit(WalletEvent::Connect(public_key));
+                    }
+                    update_accounts(&session, &events, session.accounts(), Some(public_key));
+                }
+                true
+            }
+            ProviderEvent::AccountsChanged(accounts) if !accounts.is_empty() => {
+                update_accounts(&session, &events, accounts, None);
+                true
+            }
+            ProviderEvent::Connect(None) => true,
+            ProviderEvent::AccountChanged(None)
+            | ProviderEvent::AccountsChanged(_)
+            | ProviderEvent::Disconnect => {
+                info!("Wallet disconnected");
+                session.clear();
+                events.emit(WalletEvent::Disconnect);
+                false
+            }
+        });
+        self.session.set_subscription(subscription);
     }
 
-    fn url(&self) -> String {
-        self.url.to_string()
+    /// Connects interactively, or only if the wallet already trusts the site when `silent`.
+    async fn connect_with(&mut self, silent: bool) -> Result<bool, WalletError> {
+        info!(
+            "Connecting to wallet{}...",
+            if silent { " silently" } else { "" }
+        );
+
+        if !self.session.start_connecting() {
+            self.emit_error(WalletError::WalletConnectionError);
+            return Err(WalletError::WalletConnectionError);
+        }
+
+        // A rejected silent connect only means the user has not approved the site yet.
+        let failed = |adapter: &mut Self, err: WalletError| {
+            if silent {
+                info!("Wallet did not connect silently: {}", err);
+            } else {
+                log::error!("Failed to connect wallet: {}", err);
+                adapter.emit_error(err);
+            }
+        };
+
+        let options = js_sys::Object::new();
+        if silent {
+            let _ = js_sys::Reflect::set(&options, &JsValue::from("onlyIfTrusted"), &JsValue::TRUE);
+        }
+
+        if let Wallet::Standard(ref name) = self.name {
+            let result = self
+                .cancel
+                .run(self.timeouts().connect, standard::connect(name, silent))
+                .await;
+            self.session.with(|state| state.connecting = false);
+
+            return match result {
+                Ok(Ok(public_key)) => {
+                    info!("Connected to wallet with public key: {:?}", public_key);
+                    self.set_connected(public_key).await;
+                    Ok(true)
+                }
+                Ok(Err(err)) => {
+                    failed(self, err);
+                    Ok(false)
+                }
+                Err(err) => {
+                    log::error!("Failed to connect wallet: {}", err);
+                    self.emit_error(err.clone());
+                    Err(err)
+                }
+            };
+        }
+
+        let promise = match self.name {
+            Wallet::Phantom => phantom().ok().map(|phantom| phantom.connect(&options)),
+            Wallet::Solflare => solflare().ok().map(|solflare| solflare.connect(&options)),
+            Wallet::Backpack => backpack().ok().map(|backpack| backpack.connect(&options)),
+            Wallet::Standard(_) => None,
+        };
+
+        let Some(promise) = promise else {
+            self.session.with(|state| state.connecting = false);
+            if silent {
+                info!("{} is not available", self.name());
+                return Ok(false);
+            }
+            // Mobile wallets can open the page in their own browser, where they are injected.
+            let url = match self.ready_state() {
+                WalletReadyState::Loadable => self.browse_url().unwrap_or_else(|| self.url.clone()),
+                _ => self.url.clone(),
+            };
+            let opened = window().and_then(|window| window.open_with_url(&url).ok().flatten());
+            if opened.is_none() {
+                log::error!("Failed to open {}", url);
+                self.emit_error(WalletError::WalletWindowBlockedError);
+                return Err(WalletError::WalletWindowBlockedError);
+            }
+            return Ok(false);
+        };
+
+        let result = self
+            .cancel
+            .run(self.timeouts().connect, resolve(promise))
+            .await;
+        self.session.with(|state| state.connecting = false);
+
+        match result {
+            Ok(Ok(response)) => {
+                info!("Wallet connected");
+
+                // Wallets answer with `{ publicKey }`; otherwise read it off the provider.
+                let public_key = js_sys::Reflect::get(&response, &JsValue::from("publicKey"))
+                    .ok()
+                    .and_then(|key| js_public_key(&key))
+                    .or_else(|| {
+                        let key = match self.name {
+                            Wallet::Phantom => phantom().and_then(|phantom| phantom.publicKey()),
+                            Wallet::Solflare => {
+                                solflare().and_then(|solflare| solflare.publicKey())
+                            }
+                            Wallet::Backpack => {
+                                backpack().and_then(|backpack| backpack.publicKey())
+                            }
+                            Wallet::Standard(_) => Ok(JsValue::UNDEFINED),
+                        };
+                        key.ok().and_then(|key| js_public_key(&key))
+                    });
+
+                match public_key {
+                    Some(public_key) => {
+                        info!("Connected to wallet with public key: {:?}", public_key);
+                        self.set_connected(public_key).await;
+                        Ok(true)
+                    }
+                    None => {
+                        info!("Public key is undefined");
+                        Ok(false)
+                    }
+                }
+            }
+            Ok(Err(err)) => {
+                failed(self, WalletError::from_js(WalletOperation::Connect, &err));
+                Ok(false)
+            }
+            Err(err) => {
+                log::error!("Failed to connect wallet: {}", err);
+                self.emit_error(err.clone());
+                Err(err)
+            }
+        }
     }
 
-    fn icon(&self) -> String {
-        self.icon.to_string()
+    /// A link opening the current page in the wallet's in-app browser.
+    fn browse_url(&self) -> Option<String> {
+        let location = window()?.location();
+        let page = js_sys::encode_uri_component(&location.href().ok()?);
+        let origin = js_sys::encode_uri_component(&location.origin().ok()?);
+
+        match self.name {
+            Wallet::Phantom => Some(format!(
+                "https://phantom.app/ul/browse/{}?ref={}",
+                page, origin
+            )),
+            Wallet::Solflare => Some(format!(
+                "https://solflare.com/ul/v1/browse/{}?ref={}",
+                page, origin
+            )),
+            Wallet::Backpack | Wallet::Standard(_) => None,
+        }
     }
 
-    fn ready_state(&self) -> WalletReadyState {
-        self.ready_state.clone()
+    /// Resolves with the wallet's ready state once it differs from `ready_state`, e.g. when the
+    /// wallet injects itself after the page has loaded.
+    pub async fn wait_for_ready_state_change(
+        &self,
+        ready_state: WalletReadyState,
+    ) -> WalletReadyState {
+        loop {
+            let current = self.ready_state();
+            if current != ready_state {
+                return current;
+            }
+            sleep(READY_STATE_POLL_INTERVAL).await;
+        }
     }
 
-    fn public_key(&self) -> Option<Pubkey> {
-        self.public_key
+    /// Subscribes `listener` without polling the ready state for it, for listeners the providers
+    /// keep for as long as the adapter lives.
+    #[cfg(any(feature = "dio", feature = "lep", feature = "yew"))]
+    pub(crate) fn subscribe_unwatched(
+        &self,
+        listener: impl Fn(&WalletEvent) + 'static,
+    ) -> Subscription {
+        self.events.subscribe_unwatched(listener)
     }
 
-    fn connecting(&self) -> bool {
-        self.connecting
+    /// Polls the wallet's ready state and emits [`WalletEvent::ReadyStateChange`] when it changes,
+    /// unless that already happens. Stops once the last subscription is dropped, or the adapter
+    /// and all of its clones are.
+    fn watch_ready_state(&self) {
+        if self.events.set_watched(true) {
+            return;
+        }
+        let wallet = self.name.clone();
+        let events = self.events.downgrade();
+        // Read now, as the task only starts once the caller yields.
+        let mut ready_state = ready_state_of(&wallet);
+
+        wasm_bindgen_futures::spawn_local(async move {
+            loop {
+                sleep(READY_STATE_POLL_INTERVAL).await;
+                let Some(events) = events.upgrade() else {
+                    return;
+                };
+                if !events.has_watchers() {
+                    events.set_watched(false);
+                    return;
+                }
+
+                let current = ready_state_of(&wallet);
+                if current != ready_state {
+                    info!("{:?} is now {:?}", wallet, current);
+                    ready_state = current;
+                    events.emit(WalletEvent::ReadyStateChange(current));
+                }
+            }
+        });
     }
 
-    async fn auto_connect(&mut self) -> Result<bool, WalletError> {
-        self.connect().await
+    /// The wallet this adapter talks to.
+    pub fn wallet(&self) -> &Wallet {
+        &self.name
     }
 
-    async fn connect(&mut self) -> Result<bool, WalletError> {
-        info!("Connecting to wallet...");
+    /// Turns off the local ed25519 check of the signatures the wallet returns, which is on by
+    /// default. Only the wallet's word then vouches for what it hands back.
+    ///
+    /// Signatures returned by sign-and-send calls are never checked: the wallet submits those
+    /// transactions itself and may adjust them first, e.g. to add priority fees.
+    pub fn set_skip_signature_verification(&self, skip: bool) {
+        self.session
+            .with(|state| state.skip_signature_verification = skip);
+    }
 
-        if self.connecting {
-            self.emit_error(WalletError::WalletConnectionError);
-            return Err(WalletError::WalletConnectionError);
-        }
+    pub fn skip_signature_verification(&self) -> bool {
+        self.session.with(|state| state.skip_signature_verification)
+    }
 
-        self.connecting = true;
+    /// Sets how long connect, sign and send requests may stay pending before they fail with
+    /// `WalletTimeoutError`. None of them time out by default.
+    pub fn set_timeouts(&self, timeouts: WalletTimeouts) {
+        self.session.with(|state| state.timeouts = timeouts);
+    }
 
-        let options = js_sys::Object::new();
-        js_sys::Reflect::set(
-            &options,
-            &serde_wasm_bindgen::to_value("onlyIfTrusted").unwrap(),
-            &serde_wasm_bindgen::to_value(&true).unwrap(),
-        )
-        .unwrap();
-
-        let promise: Option<Promise> = match self.name {
-            Wallet::Phantom if !SOLANA.is_undefined() => Some(SOLANA.sign_in(&options)),
-            Wallet::Solflare if !SOLFLARE.is_undefined() => Some(SOLFLARE.connect(&options)),
-            Wallet::Backpack if !XNFT.is_undefined() => Some(BACKPACK.sign_in(&options)),
-            Wallet::Phantom | Wallet::Solflare | Wallet::Backpack => None,
+    pub fn timeouts(&self) -> WalletTimeouts {
+        self.session.with(|state| state.timeouts)
+    }
+
+    /// A handle that aborts this adapter's pending requests, shared with its clones.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Sets what happens to a request made while another one is waiting on the wallet. Requests
+    /// are queued by default.
+    pub fn set_request_policy(&self, policy: RequestPolicy) {
+        self.session.with(|state| state.request_policy = policy);
+    }
+
+    pub fn request_policy(&self) -> RequestPolicy {
+        self.session.with(|state| state.request_policy)
+    }
+
+    /// Whether a request is waiting on the wallet or queued behind one. Changes are emitted as
+    /// [`WalletEvent::PendingRequestChange`].
+    pub fn pending_request(&self) -> bool {
+        self.requests.is_pending()
+    }
+
+    /// Sets the RPC client that fills in the recent blockhash of transactions built without
+    /// one. The wallet providers use the `ConnectionProvider`'s client.
+    pub fn set_connection(&self, connection: Arc<RpcClient>) {
+        self.session
+            .with(|state| state.connection = Some(connection));
+    }
+
+    pub fn connection(&self) -> Option<Arc<RpcClient>> {
+        self.session.with(|state| state.connection.clone())
+    }
+
+    /// Sets the cluster transactions are signed for, for connections whose endpoint doesn't
+    /// tell it. `None` goes back to deriving it from the connection.
+    pub fn set_cluster(&self, cluster: Option<Cluster>) {
+        self.session.with(|state| state.cluster = cluster);
+    }
+
+    /// The cluster transactions are signed for: the one set with
+    /// [`set_cluster`](Self::set_cluster), else the connection's, else mainnet. Standard wallets
+    /// are asked to sign for its chain and refuse accounts that don't support it.
+    pub fn cluster(&self) -> Cluster {
+        self.session.with(|state| {
+            state
+                .cluster
+                .or_else(|| {
+                    state
+                        .connection
+                        .as_ref()
+                        .map(|connection| connection.cluster())
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    /// The last block height at which a transaction using `blockhash` can land, for
+    /// blockhashes this adapter filled in.
+    pub fn last_valid_block_height(&self, blockhash: &Hash) -> Option<u64> {
+        self.session
+            .with(|state| state.blockhashes.get(blockhash).copied())
+    }
+
+    /// Waits until the transaction behind `signature` reaches `commitment`, emitting
+    /// [`WalletEvent::ConfirmationProgress`] on the way, and resolves with the slot it landed in
+    /// and the error it failed with, if any.
+    ///
+    /// Transactions this adapter sent fail with [`WalletError::WalletTransactionExpiredError`]
+    /// once their blockhash expires; others are polled until the send timeout elapses or the
+    /// request is cancelled.
+    pub async fn confirm_transaction(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+    ) -> Result<Confirmation, WalletError> {
+        let Some(client) = self.connection() else {
+            log::error!("No connection to confirm transactions with");
+            return Err(WalletError::WalletConfigError);
+        };
+        let expiry = self
+            .session
+            .with(|state| {
+                state
+                    .sent
+                    .iter()
+                    .find(|(sent, _)| sent == signature)
+                    .map(|(_, blockhash)| *blockhash)
+            })
+            .map(|blockhash| self.session.expiry(blockhash))
+            .unwrap_or_default();
+
+        let result = self
+            .cancel
+            .run(
+                self.timeouts().send,
+                confirmation::confirm(&client, &self.events, *signature, commitment, expiry),
+            )
+            .await
+            .and_then(|result| result);
+        if let Err(err) = &result {
+            self.events.emit(WalletEvent::Error(err.clone()));
+        }
+        result
+    }
+
+    /// Fills in what `transactions` were built without before they are signed: a recent
+    /// blockhash, fetched once for all of them, and the connected account as fee payer.
+    async fn fill(
+        &self,
+        transactions: &mut [TransactionOrVersionedTransaction],
+    ) -> Result<(), WalletError> {
+        let fee_payer = self
+            .public_key()
+            .ok_or(WalletError::WalletNotConnectedError)?;
+
+        let blockhash = match self.connection() {
+            Some(client) if transactions.iter().any(|tx| tx.needs_blockhash()) => {
+                let (blockhash, last_valid_block_height) = self
+                    .cancel
+                    .run(
+                        self.timeouts().send,
+                        client.get_latest_blockhash_with_commitment(client.commitment_config()),
+                    )
+                    .await?
+                    .map_err(|err| {
+                        log::error!("Failed to get a recent blockhash: {:?}", err);
+                        WalletError::from_rpc(WalletOperation::GetLatestBlockhash, &err)
+                    })?;
+                info!(
+                    "Using blockhash {} valid until block {}",
+                    blockhash, last_valid_block_height
+                );
+                self.session.with(|state| {
+                    state.blockhashes.retain(|_, height| {
+                        *height + BLOCKHASH_RETENTION >= last_valid_block_height
+                    });
+                    state.blockhashes.insert(blockhash, last_valid_block_height);
+                });
+                Some(blockhash)
+            }
+            Some(_) => None,
+            None => {
+                if transactions.iter().any(|tx| tx.needs_blockhash()) {
+                    info!("No connection to fetch a recent blockhash with");
+                }
+                None
+            }
         };
 
-        if promise.is_some() {
-            let result = JsFuture::from(promise.unwrap()).await;
+        for transaction in transactions {
+            transaction.fill(&fee_payer, blockhash);
+        }
+        Ok(())
+    }
+
+    /// Simulates every transaction before the wallet is asked to sign it, and fails with
+    /// [`WalletError::WalletSimulationError`] instead of prompting when one would fail. Off by
+    /// default.
+    ///
+    /// Transactions in a batch are simulated one by one, so a transaction that depends on an
+    /// earlier one in the same batch fails its simulation.
+    pub fn set_simulate_before_signing(&self, simulate: bool) {
+        self.session
+            .with(|state| state.simulate_before_signing = simulate);
+    }
 
-            match result {
-                Ok(_response) => {
-                    // Todo use response to get pubkey
-                    // let response: MessageObject = serde_wasm_bindgen::from_value(response).unwrap();
-                    info!("Wallet connected");
+    pub fn simulate_before_signing(&self) -> bool {
+        self.session.with(|state| state.simulate_before_signing)
+    }
 
-                    let key: JsValue = match self.name {
-                        Wallet::Phantom => SOLANA.publicKey(),
-                        Wallet::Solflare => SOLFLARE.publicKey(),
-                        Wallet::Backpack => BACKPACK.publicKey(),
-                    };
+    /// Sets how [`sign_send_transaction`](Self::sign_send_transaction) gets transactions to the
+    /// cluster. [`SendStrategy::Rebroadcast`] needs a connection.
+    pub fn set_send_strategy(&self, strategy: SendStrategy) {
+        self.session.with(|state| state.send_strategy = strategy);
+    }
 
-                    if key.is_undefined() {
-                        info!("Public key is undefined");
-                    } else {
-                        let key_str: String = JsValue::into_serde(&key).unwrap();
+    pub fn send_strategy(&self) -> SendStrategy {
+        self.session.with(|state| state.send_strategy)
+    }
 
-                        let public_key = Pubkey::from_str(&key_str).unwrap();
-                        info!("Connected to wallet with public key: {:?}", public_key);
-                        self.public_key = Some(public_key);
-                        self.ready_state = WalletReadyState::Installed;
-                        self.emit_connect(public_key);
+    /// Runs `transaction` through the connection's RPC node without sending it, after filling
+    /// in what it was built without. Backpack simulates it on its own when the adapter has no
+    /// connection.
+    pub async fn simulate_transaction(
+        &self,
+        transaction: impl Into<TransactionOrVersionedTransaction>,
+    ) -> Result<SimulationReport, WalletError> {
+        let mut transaction = transaction.into();
+        if self.public_key().is_some() {
+            self.fill(std::slice::from_mut(&mut transaction)).await?;
+        }
+        self.simulate(&transaction).await
+    }
 
-                        self.connecting = false;
-                    }
+    async fn simulate(
+        &self,
+        transaction: &TransactionOrVersionedTransaction,
+    ) -> Result<SimulationReport, WalletError> {
+        let timeout = self.timeouts().send;
+
+        match self.connection() {
+            Some(client) => self
+                .cancel
+                .run(timeout, client.simulate(transaction))
+                .await?
+                .map_err(|err| {
+                    log::error!("Failed to simulate transaction: {:?}", err);
+                    WalletError::from_rpc(WalletOperation::SimulateTransaction, &err)
+                }),
+            None if self.name == Wallet::Backpack && has_method(backpack(), "simulate") => {
+                let transaction_bytes = transaction
+                    .to_bytes()
+                    .map_err(|_| WalletError::WalletSignTransactionError)?;
+                let promise = backpack().and_then(|backpack| {
+                    backpack.simulate(
+                        &Uint8Array::from(&transaction_bytes[..]),
+                        &JsValue::UNDEFINED,
+                        &JsValue::from("confirmed"),
+                        &JsValue::UNDEFINED,
+                    )
+                });
+                let response =
+                    self.cancel
+                        .run(timeout, resolve(promise))
+                        .await?
+                        .map_err(|err| {
+                            log::error!("Failed to simulate transaction: {:?}", err);
+                            WalletError::from_js(WalletOperation::SimulateTransaction, &err)
+                        })?;
+
+                // Either the simulation itself or an RPC reply wrapping it in `value`.
+                let value = js_sys::Reflect::get(&response, &JsValue::from("value"))
+                    .ok()
+                    .filter(JsValue::is_object)
+                    .unwrap_or(response);
+                from_js_json::<RpcSimulation>(&value)
+                    .map(|simulation| simulation.report(&[], Vec::new()))
+                    .ok_or_else(|| {
+                        WalletError::from_js(
+                            WalletOperation::SimulateTransaction,
+                            &JsValue::from("invalid simulation response"),
+                        )
+                    })
+            }
+            None => {
+                log::error!("No connection to simulate transactions with");
+                Err(WalletError::WalletConfigError)
+            }
+        }
+    }
+
+    /// Fails if simulating before signing is on and any of `transactions` would fail.
+    async fn check_simulations(
+        &mut self,
+        transactions: &[TransactionOrVersionedTransaction],
+    ) -> Result<(), WalletError> {
+        if !self.simulate_before_signing() {
+            return Ok(());
+        }
+
+        for transaction in transactions {
+            let report = self.simulate(transaction).await?;
+            if let Some(error) = report.error {
+                log::error!("Transaction would fail: {}", error);
+                let err = WalletError::WalletSimulationError {
+                    error,
+                    logs: report.logs,
+                };
+                self.emit_error(err.clone());
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// The accounts the wallet exposes to the site, including the active one, which signs and
+    /// is returned by `public_key`. Changes are emitted as [`WalletEvent::AccountsChanged`].
+    ///
+    /// Phantom and Solflare only ever expose the active account.
+    pub fn accounts(&self) -> Vec<Pubkey> {
+        self.session.accounts()
+    }
+
+    /// Makes one of [`accounts`](Self::accounts) the active account, emitting
+    /// [`WalletEvent::AccountChanged`].
+    pub fn select_account(&self, public_key: Pubkey) -> Result<(), WalletError> {
+        if self.public_key().is_none() {
+            return Err(WalletError::WalletNotConnectedError);
+        }
+        let accounts = self.accounts();
+        if !accounts.contains(&public_key) {
+            log::error!("{} is not an account of {}", public_key, self.name());
+            return Err(WalletError::WalletAccountError);
+        }
+
+        update_accounts(&self.session, &self.events, accounts, Some(public_key));
+        Ok(())
+    }
+
+    /// Asks the wallet for its accounts again, e.g. after the user added one in Backpack, which
+    /// does not report that on its own.
+    pub async fn refresh_accounts(&self) -> Result<Vec<Pubkey>, WalletError> {
+        let public_key = self
+            .public_key()
+            .ok_or(WalletError::WalletNotConnectedError)?;
+
+        let accounts = match self.name {
+            Wallet::Standard(ref name) => standard::public_keys(name),
+            Wallet::Backpack if has_method(backpack(), "_backpackGetAccounts") => {
+                let promise = backpack().and_then(|backpack| backpack.backpack_get_accounts());
+                let response = self
+                    .cancel
+                    .run(self.timeouts().connect, resolve(promise))
+                    .await?
+                    .map_err(|err| WalletError::from_js(WalletOperation::Connect, &err))?;
+                backpack_accounts(&response)
+            }
+            _ => vec![public_key],
+        };
+
+        update_accounts(&self.session, &self.events, accounts, None);
+        Ok(self.accounts())
+    }
+
+    /// Waits for the wallet to be free, for at most `timeout` and until cancelled, or fails if
+    /// it is busy and the policy is to reject.
+    async fn request(&self, timeout: Option<Duration>) -> Result<Request, WalletError> {
+        let request = self
+            .cancel
+            .run(
+                timeout,
+                self.requests.enter(self.request_policy(), &self.events),
+            )
+            .await
+            .and_then(|request| request);
+        if let Err(ref err) = request {
+            info!("{} did not get to the request: {}", self.name(), err);
+            self.events.emit(WalletEvent::Error(err.clone()));
+        }
+        request
+    }
+
+    fn verify_signature(
+        &self,
+        public_key: &Pubkey,
+        message: &[u8],
+        signature: &Signature,
+    ) -> Result<(), WalletError> {
+        if self.skip_signature_verification() || signature.verify(public_key.as_ref(), message) {
+            Ok(())
+        } else {
+            log::error!("Wallet returned an invalid signature for {}", public_key);
+            Err(WalletError::WalletInvalidSignatureError)
+        }
+    }
+
+    /// Checks our signature, which must be present, and every other signature already in
+    /// `transaction`.
+    fn verify_transaction(
+        &self,
+        transaction: TransactionOrVersionedTransaction,
+    ) -> Result<TransactionOrVersionedTransaction, WalletError> {
+        let public_key = self
+            .public_key()
+            .ok_or(WalletError::WalletNotConnectedError)?;
+        let signer_index = transaction
+            .signer_index(&public_key)
+            .ok_or(WalletError::WalletSignTransactionError)?;
+        let message = transaction.message_data();
+
+        for (index, key) in transaction.signer_keys().iter().enumerate() {
+            match transaction.signatures().get(index) {
+                Some(signature) if index == signer_index || *signature != Signature::default() => {
+                    self.verify_signature(key, &message, signature)?
                 }
-                Err(err) => {
-                    log::error!("Failed to connect wallet: {:?}", err);
+                None if index == signer_index => {
+                    return Err(WalletError::WalletSignTransactionError)
                 }
+                _ => {}
             }
+        }
+
+        Ok(transaction)
+    }
+
+    /// Checks that the wallet signed exactly `message`.
+    fn verify_message(
+        &self,
+        message: &[u8],
+        output: SignMessageOutput,
+    ) -> Result<SignMessageOutput, WalletError> {
+        let public_key = self
+            .public_key()
+            .ok_or(WalletError::WalletNotConnectedError)?;
+        if output.signed_message != message {
+            log::error!("Wallet signed a different message than the one requested");
+            return Err(WalletError::WalletInvalidSignatureError);
+        }
+        self.verify_signature(&public_key, &output.signed_message, &output.signature)?;
+        Ok(output)
+    }
+
+    /// Checks that a wallet's native sign in signed the SIWS message for `input`: the canonical
+    /// text for the signing account, with every field set in `input` unchanged. Wallets fill in
+    /// the domain with the page's host when `input` has none.
+    fn verify_sign_in(
+        &self,
+        input: &SignInInput,
+        output: &SignInOutput,
+    ) -> Result<(), WalletError> {
+        self.verify_signature(&output.account, &output.signed_message, &output.signature)?;
+
+        let signed = std::str::from_utf8(&output.signed_message)
+            .ok()
+            .and_then(parse_sign_in_message)
+            .ok_or_else(|| {
+                log::error!("Wallet signed a message that is not a sign in message");
+                WalletError::WalletSignInError
+            })?;
+
+        let mut expected = input.clone();
+        if expected.domain.is_none() {
+            expected.domain = window().and_then(|window| window.location().host().ok());
+        }
+        let mismatch = if signed.address != Some(output.account.to_string()) {
+            Some("address")
         } else {
-            let window = window().expect("no global `window` exists");
-            window
-                .open_with_url(&self.url)
-                .expect("failed to open a new tab");
+            mismatched_field(&expected, &signed)
+        };
+        match mismatch {
+            Some(field) => {
+                log::error!("Wallet signed a sign in message with a different {}", field);
+                Err(WalletError::WalletSignInError)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the transaction with the wallet's signature in its slot.
+    pub async fn sign_transaction(
+        &mut self,
+        transaction: impl Into<TransactionOrVersionedTransaction>,
+    ) -> Result<TransactionOrVersionedTransaction, WalletError> {
+        info!("Signing transaction...");
+        let _request = self.request(self.timeouts().sign).await?;
+
+        if self.public_key().is_none() {
+            self.emit_error(WalletError::WalletNotConnectedError);
+            return Err(WalletError::WalletNotConnectedError);
         }
 
-        Ok(!self.connecting)
+        self.sign(transaction.into()).await
     }
 
-    async fn disconnect(&mut self) -> Result<bool, WalletError> {
-        info!("Disconnecting from wallet...");
-        let mut confirmed = false;
+    async fn sign(
+        &mut self,
+        mut transaction: TransactionOrVersionedTransaction,
+    ) -> Result<TransactionOrVersionedTransaction, WalletError> {
+        self.fill(std::slice::from_mut(&mut transaction)).await?;
+        self.check_simulations(std::slice::from_ref(&transaction))
+            .await?;
+        let transaction_bytes = transaction
+            .to_bytes()
+            .map_err(|_| WalletError::WalletSignTransactionError)?;
 
-        if self.public_key.is_none() {
-            self.emit_error(WalletError::WalletDisconnectedError);
-            return Err(WalletError::WalletDisconnectedError);
+        if let Wallet::Standard(ref name) = self.name {
+            let public_key = self.public_key().unwrap_or_default();
+            let signed_bytes = self
+                .cancel
+                .run(
+                    self.timeouts().sign,
+                    standard::sign_transaction(
+                        name,
+                        &public_key,
+                        self.cluster().chain(),
+                        &transaction_bytes,
+                    ),
+                )
+                .await??;
+            return signed_transaction_from_bytes(&transaction, &signed_bytes)
+                .and_then(|signed| self.verify_transaction(signed));
         }
 
-        self.public_key = None;
-        self.ready_state = WalletReadyState::NotDetected;
-        self.emit_disconnect();
-        let promise: Promise = match self.name {
-            Wallet::Phantom => SOLANA.disconnect(),
-            Wallet::Solflare => SOLFLARE.disconnect(),
-            Wallet::Backpack => BACKPACK.disconnect(),
+        let transaction_js_array = Uint8Array::from(&transaction_bytes[..]);
+
+        let bs58_tx = bs58::encode(transaction_bytes).into_string();
+
+        let options = request_options("signTransaction", &JsValue::from(bs58_tx))
+            .map_err(|_| WalletError::WalletSignTransactionError)?;
+
+        let public_key = self.public_key().unwrap_or_default();
+        let promise = match self.name {
+            Wallet::Phantom => phantom().and_then(|phantom| phantom.request(&options)),
+            Wallet::Solflare => solflare().and_then(|solflare| solflare.request(&options)),
+            Wallet::Backpack => backpack().and_then(|backpack| {
+                backpack.sign_transaction(
+                    &transaction_js_array,
+                    &JsValue::from(public_key),
+                    &JsValue::from(""),
+                    &JsValue::from("uuid"),
+                )
+            }),
+            Wallet::Standard(_) => return Err(WalletError::WalletConfigError),
         };
-        let result = JsFuture::from(promise).await;
+
+        let result = self
+            .cancel
+            .run(self.timeouts().sign, resolve(promise))
+            .await?;
 
         match result {
-            Ok(_) => {
-                confirmed = true;
-                info!("Disconnected from wallet");
+            Ok(response) => {
+                let signed = signed_transaction(&transaction, &response, self.public_key())
+                    .and_then(|signed| self.verify_transaction(signed))?;
+                info!("Got signatures: {:?}", signed.signatures());
+                Ok(signed)
             }
             Err(err) => {
-                log::error!("Failed to disconnect wallet: {:?}", err);
+                log::error!("Failed to sign transaction: {:?}", err);
+                Err(WalletError::from_js(WalletOperation::SignTransaction, &err))
             }
         }
+    }
 
-        Ok(confirmed)
+    /// Signs a batch of transactions behind a single wallet prompt.
+    ///
+    /// Fails as a whole if the request is rejected; otherwise returns one result per
+    /// transaction, in the order they were given.
+    pub async fn sign_all_transactions(
+        &mut self,
+        transactions: impl IntoIterator<Item = impl Into<TransactionOrVersionedTransaction>>,
+    ) -> Result<Vec<Result<TransactionOrVersionedTransaction, WalletError>>, WalletError> {
+        let _request = self.request(self.timeouts().sign).await?;
+        self.sign_all(transactions.into_iter().map(Into::into).collect())
+            .await
     }
 
-    async fn send_transaction(
+    async fn sign_all(
         &mut self,
-        client: Arc<RpcClient>,
-        transaction: TransactionOrVersionedTransaction,
-    ) -> Result<Signature, WalletError> {
-        info!("Sending transaction...");
+        mut transactions: Vec<TransactionOrVersionedTransaction>,
+    ) -> Result<Vec<Result<TransactionOrVersionedTransaction, WalletError>>, WalletError> {
+        info!("Signing all transactions...");
 
-        if self.public_key.is_none() {
+        if self.public_key().is_none() {
             self.emit_error(WalletError::WalletNotConnectedError);
             return Err(WalletError::WalletNotConnectedError);
         }
-        let signature = match transaction {
-            TransactionOrVersionedTransaction::Transaction(tx) => client
-                .send_and_confirm_transaction(&tx)
-                .await
-                .map_err(|_| WalletError::WalletSendTransactionError)?,
-            TransactionOrVersionedTransaction::VersionedTransaction(_vtx) => {
-                // TODO: Add support
-                // client
-                //     .async_send_versioned_transaction(&vtx)
-                //     .await
-                //     .map_err(|_| WalletError::WalletSendTransactionError)?
-                Signature::default()
+        self.fill(&mut transactions).await?;
+        self.check_simulations(&transactions).await?;
+
+        let transactions_bytes = transactions
+            .iter()
+            .map(|transaction| transaction.to_bytes())
+            .collect::<bincode::Result<Vec<_>>>()
+            .map_err(|_| WalletError::WalletSignTransactionError)?;
+
+        if let Wallet::Standard(ref name) = self.name {
+            let public_key = self.public_key().unwrap_or_default();
+            let outputs = self
+                .cancel
+                .run(
+                    self.timeouts().sign,
+                    standard::sign_all_transactions(
+                        name,
+                        &public_key,
+                        self.cluster().chain(),
+                        &transactions_bytes,
+                    ),
+                )
+                .await??;
+            return Ok(transactions
+                .iter()
+                .zip(outputs)
+                .map(|(transaction, signed_bytes)| {
+                    signed_bytes
+                        .ok_or(WalletError::WalletSignTransactionError)
+                        .and_then(|bytes| signed_transaction_from_bytes(transaction, &bytes))
+                        .and_then(|signed| self.verify_transaction(signed))
+                })
+                .collect());
+        }
+
+        let transactions_js_array: Array = transactions_bytes
+            .iter()
+            .map(|bytes| JsValue::from(Uint8Array::from(&bytes[..])))
+            .collect();
+
+        let bs58_txs: Vec<String> = transactions_bytes
+            .iter()
+            .map(|bytes| bs58::encode(bytes).into_string())
+            .collect();
+
+        let bs58_txs: Array = bs58_txs.into_iter().map(JsValue::from).collect();
+        let options = request_options("signAllTransactions", &bs58_txs)
+            .map_err(|_| WalletError::WalletSignTransactionError)?;
+
+        let public_key = self.public_key().unwrap_or_default();
+        let promise = match self.name {
+            Wallet::Phantom => phantom().and_then(|phantom| phantom.request(&options)),
+            Wallet::Solflare => solflare().and_then(|solflare| solflare.request(&options)),
+            Wallet::Backpack => backpack().and_then(|backpack| {
+                backpack.sign_all_transactions(
+                    &transactions_js_array,
+                    &JsValue::from(public_key),
+                    &JsValue::from(""),
+                    &JsValue::from("uuid"),
+                )
+            }),
+            Wallet::Standard(_) => return Err(WalletError::WalletConfigError),
+        };
+
+        let responses = match self
+            .cancel
+            .run(self.timeouts().sign, resolve(promise))
+            .await?
+        {
+            Ok(responses) => responses
+                .dyn_into::<Array>()
+                .map_err(|_| WalletError::WalletSignTransactionError)?,
+            Err(err) => {
+                log::error!("Failed to sign transactions: {:?}", err);
+                return Err(WalletError::from_js(
+                    WalletOperation::SignAllTransactions,
+                    &err,
+                ));
             }
         };
 
+        let public_key = self.public_key();
+        Ok(transactions
+            .iter()
+            .enumerate()
+            .map(|(index, transaction)| {
+                let response = responses.get(index as u32);
+                if response.is_undefined() {
+                    return Err(WalletError::WalletSignTransactionError);
+                }
+                signed_transaction(transaction, &response, public_key)
+                    .and_then(|signed| self.verify_transaction(signed))
+            })
+            .collect())
+    }
+
+    pub async fn sign_send_transaction(
+        &mut self,
+        transaction: impl Into<TransactionOrVersionedTransaction>,
+    ) -> Result<Signature, WalletError> {
+        self.sign_send_transaction_with_options(transaction, SendOptions::default())
+            .await
+    }
+
+    /// Signs and submits `transaction`, passing `options` on to the wallet, or to the RPC node
+    /// when rebroadcasting or when the wallet can only sign. Those need a connection.
+    pub async fn sign_send_transaction_with_options(
+        &mut self,
+        transaction: impl Into<TransactionOrVersionedTransaction>,
+        options: SendOptions,
+    ) -> Result<Signature, WalletError> {
+        if let SendStrategy::Rebroadcast(config) = self.send_strategy() {
+            return self
+                .sign_rebroadcast_transaction(transaction, config.with_options(&options))
+                .await
+                .and_then(|report| report.confirmation.into_result())
+                .map(|confirmation| confirmation.signature);
+        }
+
+        let signature = self.sign_send(transaction.into(), options).await?;
         self.emit_transaction_sent(signature);
-        info!("Transaction sent: {}", signature);
         Ok(signature)
     }
 
-    async fn sign_transaction(
+    /// Has the wallet sign and submit `transaction`, or submits it through the connection when
+    /// the wallet can only sign.
+    async fn sign_send(
         &mut self,
-        transaction: Transaction,
+        transaction: TransactionOrVersionedTransaction,
+        options: SendOptions,
     ) -> Result<Signature, WalletError> {
-        info!("Signing transaction...");
+        info!("Signing and sending transaction...");
+        let _request = self.request(self.timeouts().send).await?;
 
-        if self.public_key.is_none() {
+        if self.public_key().is_none() {
             self.emit_error(WalletError::WalletNotConnectedError);
             return Err(WalletError::WalletNotConnectedError);
         }
 
-        let transaction_bytes = bincode::serialize(&transaction)
+        // Standard wallets that can only sign leave sending to the connection.
+        if matches!(self.name, Wallet::Standard(_))
+            && !self
+                .features()
+                .iter()
+                .any(|feature| feature == SOLANA_SIGN_AND_SEND_TRANSACTION)
+        {
+            let Some(client) = self.connection() else {
+                log::error!("No connection to send transactions through");
+                return Err(WalletError::WalletConfigError);
+            };
+            let signed = self.sign(transaction).await?;
+            let signature = self
+                .cancel
+                .run(
+                    self.timeouts().send,
+                    client.send_with_config(&signed, client.send_config(&options)),
+                )
+                .await?
+                .map_err(|err| {
+                    log::error!("Failed to send transaction: {:?}", err);
+                    WalletError::from_rpc(WalletOperation::SendTransaction, &err)
+                })?;
+            info!("Got signature: {:?}", signature);
+            self.session
+                .record_sent(signature, *signed.recent_blockhash());
+            return Ok(signature);
+        }
+
+        let mut transaction = transaction;
+        self.fill(std::slice::from_mut(&mut transaction)).await?;
+        self.check_simulations(std::slice::from_ref(&transaction))
+            .await?;
+        let blockhash = *transaction.recent_blockhash();
+        let transaction_bytes = transaction
+            .to_bytes()
             .map_err(|_| WalletError::WalletSignTransactionError)?;
+        let send_options = serde_wasm_bindgen::to_value(&options)
+            .map_err(|_| WalletError::WalletSendTransactionError)?;
+
+        if let Wallet::Standard(ref name) = self.name {
+            let public_key = self.public_key().unwrap_or_default();
+            let signature = self
+                .cancel
+                .run(
+                    self.timeouts().send,
+                    standard::sign_and_send_transaction(
+                        name,
+                        &public_key,
+                        self.cluster().chain(),
+                        &transaction_bytes,
+                        &send_options,
+                    ),
+                )
+                .await??;
+            info!("Got signature: {:?}", signature);
+            self.session.record_sent(signature, blockhash);
+            return Ok(signature);
+        }
 
         let transaction_js_array = Uint8Array::from(&transaction_bytes[..]);
 
         let bs58_tx = bs58::encode(transaction_bytes).into_string();
 
-        let options = js_sys::Object::new();
-        js_sys::Reflect::set(
-            &options,
-            &serde_wasm_bindgen::to_value("method").unwrap(),
-            &serde_wasm_bindgen::to_value("signTransaction").unwrap(),
-        )
-        .expect("Failed to set method in options");
-
-        let params = js_sys::Object::new();
-        js_sys::Reflect::set(
-            &params,
-            &serde_wasm_bindgen::to_value("message").unwrap(),
-            &serde_wasm_bindgen::to_value(&bs58_tx).unwrap(),
-        )
-        .expect("Failed to set message in params");
-
-        js_sys::Reflect::set(
-            &options,
-            &serde_wasm_bindgen::to_value("params").unwrap(),
-            &JsValue::from(&params),
-        )
-        .expect("Failed to set params in options");
-
-        let promise: Promise = match self.name {
-            Wallet::Phantom => SOLANA.request(&options),
-            Wallet::Solflare => SOLFLARE.request(&options),
-            Wallet::Backpack => BACKPACK.sign_transaction(
-                &transaction_js_array,
-                &JsValue::from(self.public_key.unwrap()),
-                &JsValue::from(""),
-                &JsValue::from("uuid"),
-            ),
+        let request = request_options("signAndSendTransaction", &JsValue::from(bs58_tx))
+            .and_then(|request| {
+                let params = js_sys::Reflect::get(&request, &JsValue::from("params"))?;
+                js_sys::Reflect::set(&params, &JsValue::from("options"), &send_options)?;
+                Ok(request)
+            })
+            .map_err(|_| WalletError::WalletSignTransactionError)?;
+
+        let promise = match self.name {
+            Wallet::Phantom => phantom().and_then(|phantom| phantom.request(&request)),
+            Wallet::Solflare => solflare().and_then(|solflare| solflare.request(&request)),
+            Wallet::Backpack => backpack().and_then(|backpack| {
+                backpack.sign_and_send_transaction(&transaction_js_array, &send_options)
+            }),
+            Wallet::Standard(_) => return Err(WalletError::WalletConfigError),
         };
 
-        let result = JsFuture::from(promise).await;
+        let result = self
+            .cancel
+            .run(self.timeouts().send, resolve(promise))
+            .await?;
 
         match result {
-            Ok(json_str) => {
-                let deserialized: SignaturesObject = JsValue::into_serde(&json_str).unwrap();
+            Ok(response) => {
+                let signature = response
+                    .as_string()
+                    .or_else(|| {
+                        from_js_json::<JsSignatureObject>(&response)
+                            .map(|response| response.signature)
+                    })
+                    .and_then(|signature| Signature::from_str(&signature).ok())
+                    .ok_or(WalletError::WalletSendTransactionError)?;
+                info!("Got signature: {:?}", signature);
+                self.session.record_sent(signature, blockhash);
+
+                Ok(signature)
+            }
+            Err(err) => {
+                log::error!("Failed to sign and send transaction: {:?}", err);
+                Err(WalletError::from_js(
+                    WalletOperation::SignAndSendTransaction,
+                    &err,
+                ))
+            }
+        }
+    }
 
-                let signature_map = &deserialized.signatures[0].signature;
-                let mut signature_bytes = [0u8; 64];
+    /// Has the wallet sign `transaction`, then sends it through the connection and resends it
+    /// every `config.interval` until it confirms or its blockhash expires.
+    ///
+    /// The wallet is free for other requests once it signed.
+    pub async fn sign_rebroadcast_transaction(
+        &mut self,
+        transaction: impl Into<TransactionOrVersionedTransaction>,
+        config: RebroadcastConfig,
+    ) -> Result<RebroadcastReport, WalletError> {
+        info!("Signing and rebroadcasting transaction...");
+        let request = self.request(self.timeouts().send).await?;
 
-                for (key, value) in signature_map.iter() {
-                    let index: usize = key.parse().unwrap();
-                    signature_bytes[index] = value.as_u64().unwrap() as u8;
-                }
+        if self.public_key().is_none() {
+            self.emit_error(WalletError::WalletNotConnectedError);
+            return Err(WalletError::WalletNotConnectedError);
+        }
+        let Some(client) = self.connection() else {
+            log::error!("No connection to send transactions through");
+            return Err(WalletError::WalletConfigError);
+        };
 
-                let signature = Signature::new(&signature_bytes);
-                info!("Got signature: {:?}", signature);
-                Ok(signature)
+        let signed = self.sign(transaction.into()).await?;
+        drop(request);
+
+        let blockhash = *signed.recent_blockhash();
+        let result = self
+            .cancel
+            .run(
+                self.timeouts().send,
+                rebroadcast::rebroadcast(
+                    &client,
+                    &self.events,
+                    &signed,
+                    self.session.expiry(blockhash),
+                    config,
+                ),
+            )
+            .await
+            .and_then(|result| result);
+
+        match result {
+            Ok(report) => {
+                info!(
+                    "Transaction {} landed after {} resends",
+                    report.signature, report.resends
+                );
+                self.session.record_sent(report.signature, blockhash);
+                Ok(report)
             }
             Err(err) => {
-                log::error!("Failed to sign transaction: {:?}", err);
-                Err(WalletError::WalletSignTransactionError)
+                self.emit_error(err.clone());
+                Err(err)
+            }
+        }
+    }
+
+    /// Signs and submits a batch of transactions, returning a signature or an error for each.
+    ///
+    /// Uses the wallet's own batch submission when it has one; otherwise signs everything
+    /// behind a single prompt and sends each transaction through the adapter's connection.
+    pub async fn sign_send_all_transactions(
+        &mut self,
+        transactions: impl IntoIterator<Item = impl Into<TransactionOrVersionedTransaction>>,
+        mode: SendMode,
+    ) -> Result<Vec<Result<Signature, WalletError>>, WalletError> {
+        self.sign_send_all_transactions_with_options(transactions, mode, SendOptions::default())
+            .await
+    }
+
+    /// [`sign_send_all_transactions`](Self::sign_send_all_transactions) with `options` for
+    /// every transaction.
+    pub async fn sign_send_all_transactions_with_options(
+        &mut self,
+        transactions: impl IntoIterator<Item = impl Into<TransactionOrVersionedTransaction>>,
+        mode: SendMode,
+        options: SendOptions,
+    ) -> Result<Vec<Result<Signature, WalletError>>, WalletError> {
+        info!("Signing and sending all transactions...");
+        let _request = self.request(self.timeouts().send).await?;
+
+        if self.public_key().is_none() {
+            self.emit_error(WalletError::WalletNotConnectedError);
+            return Err(WalletError::WalletNotConnectedError);
+        }
+
+        let mut transactions: Vec<TransactionOrVersionedTransaction> =
+            transactions.into_iter().map(Into::into).collect();
+        self.fill(&mut transactions).await?;
+        let blockhashes: Vec<Hash> = transactions
+            .iter()
+            .map(|transaction| *transaction.recent_blockhash())
+            .collect();
+
+        let native =
+            self.name == Wallet::Phantom && has_method(phantom(), "signAndSendAllTransactions");
+
+        let results = if native {
+            self.check_simulations(&transactions).await?;
+            let transactions_js_array: Array = transactions
+                .iter()
+                .map(|transaction| {
+                    transaction
+                        .to_bytes()
+                        .map(|bytes| JsValue::from(Uint8Array::from(&bytes[..])))
+                })
+                .collect::<bincode::Result<_>>()
+                .map_err(|_| WalletError::WalletSignTransactionError)?;
+
+            let send_options = serde_wasm_bindgen::to_value(&options)
+                .map_err(|_| WalletError::WalletSendTransactionError)?;
+            js_sys::Reflect::set(
+                &send_options,
+                &JsValue::from("mode"),
+                &JsValue::from(mode.as_str()),
+            )
+            .map_err(|_| WalletError::WalletSendTransactionError)?;
+
+            let promise = phantom().and_then(|phantom| {
+                phantom.sign_and_send_all_transactions(&transactions_js_array, &send_options)
+            });
+            let response = self
+                .cancel
+                .run(self.timeouts().send, resolve(promise))
+                .await?
+                .map_err(|err| {
+                    log::error!("Failed to sign and send transactions: {:?}", err);
+                    WalletError::from_js(WalletOperation::SignAndSendTransaction, &err)
+                })?;
+            let response: JsSignaturesObject =
+                from_js_json(&response).ok_or(WalletError::WalletSendTransactionError)?;
+
+            (0..transactions.len())
+                .map(|index| {
+                    response
+                        .signatures
+                        .get(index)
+                        .and_then(|signature| signature.as_str())
+                        .and_then(|signature| Signature::from_str(signature).ok())
+                        .ok_or(WalletError::WalletSendTransactionError)
+                })
+                .collect()
+        } else {
+            let Some(client) = self.connection() else {
+                log::error!("No connection to send transactions through");
+                return Err(WalletError::WalletConfigError);
+            };
+            let signed = self.sign_all(transactions).await?;
+            self.cancel
+                .run(
+                    self.timeouts().send,
+                    send_all(&client, &self.session, &self.events, signed, mode, options),
+                )
+                .await?
+        };
+
+        for (result, blockhash) in results.iter().zip(blockhashes) {
+            if let Ok(signature) = result {
+                self.session.record_sent(*signature, blockhash);
+                self.emit_transaction_sent(*signature);
             }
         }
+        Ok(results)
     }
 
-    async fn sign_send_transaction(
+    /// Sends an already signed `transaction` through `client` with `options` and waits until it
+    /// reaches the client's commitment. Fails with
+    /// [`WalletError::WalletTransactionFailedError`] if it landed but failed.
+    pub async fn send_transaction_with_options(
         &mut self,
-        transaction: Transaction,
+        client: Arc<RpcClient>,
+        transaction: TransactionOrVersionedTransaction,
+        options: SendOptions,
     ) -> Result<Signature, WalletError> {
-        info!("Signing and sending transaction...");
+        info!("Sending transaction...");
 
-        if self.public_key.is_none() {
+        if self.public_key().is_none() {
             self.emit_error(WalletError::WalletNotConnectedError);
             return Err(WalletError::WalletNotConnectedError);
         }
+        let signature = self
+            .cancel
+            .run(
+                self.timeouts().send,
+                send_and_confirm(&client, &self.session, &self.events, &transaction, options),
+            )
+            .await??;
+        self.session
+            .record_sent(signature, *transaction.recent_blockhash());
 
-        let transaction_bytes = bincode::serialize(&transaction)
-            .map_err(|_| WalletError::WalletSignTransactionError)?;
+        self.emit_transaction_sent(signature);
+        info!("Transaction sent: {}", signature);
+        Ok(signature)
+    }
 
-        let transaction_js_array = Uint8Array::from(&transaction_bytes[..]);
+    /// Signs the raw `message` bytes, shown to the user as text.
+    pub async fn sign_message(
+        &mut self,
+        message: impl AsRef<[u8]>,
+    ) -> Result<SignMessageOutput, WalletError> {
+        self.sign_message_with_display(message, MessageDisplay::default())
+            .await
+    }
 
-        let bs58_tx = bs58::encode(transaction_bytes).into_string();
+    /// Signs the raw `message` bytes. `display` tells wallets that support it (Phantom and
+    /// Solflare) whether to show the message as text or as hex.
+    pub async fn sign_message_with_display(
+        &mut self,
+        message: impl AsRef<[u8]>,
+        display: MessageDisplay,
+    ) -> Result<SignMessageOutput, WalletError> {
+        let _request = self.request(self.timeouts().sign).await?;
+        self.sign_message_bytes(message.as_ref(), display).await
+    }
 
-        let options = js_sys::Object::new();
-        js_sys::Reflect::set(
-            &options,
-            &serde_wasm_bindgen::to_value("method").unwrap(),
-            &serde_wasm_bindgen::to_value("signAndSendTransaction").unwrap(),
-        )
-        .expect("Failed to set method in options");
-
-        let params = js_sys::Object::new();
-        js_sys::Reflect::set(
-            &params,
-            &serde_wasm_bindgen::to_value("message").unwrap(),
-            &serde_wasm_bindgen::to_value(&bs58_tx).unwrap(),
-        )
-        .expect("Failed to set message in params");
-
-        js_sys::Reflect::set(
-            &options,
-            &serde_wasm_bindgen::to_value("params").unwrap(),
-            &JsValue::from(&params),
-        )
-        .expect("Failed to set params in options");
-
-        let promise: Promise = match self.name {
-            Wallet::Phantom => SOLANA.request(&options),
-            Wallet::Solflare => SOLFLARE.request(&options),
-            Wallet::Backpack => BACKPACK.sign_and_send_transaction(&transaction_js_array, &options),
+    async fn sign_message_bytes(
+        &mut self,
+        message_bytes: &[u8],
+        display: MessageDisplay,
+    ) -> Result<SignMessageOutput, WalletError> {
+        info!("Signing message...");
+
+        if self.public_key().is_none() {
+            self.emit_error(WalletError::WalletNotConnectedError);
+            return Err(WalletError::WalletNotConnectedError);
+        }
+        if message_bytes.is_empty() {
+            log::error!("Refusing to sign an empty message");
+            self.emit_error(WalletError::WalletSignMessageError);
+            return Err(WalletError::WalletSignMessageError);
+        }
+
+        if let Wallet::Standard(ref name) = self.name {
+            let public_key = self.public_key().unwrap_or_default();
+            let output = self
+                .cancel
+                .run(
+                    self.timeouts().sign,
+                    standard::sign_message(name, &public_key, message_bytes),
+                )
+                .await??;
+            info!("Message signed: {:?}", output.signature);
+            return self.verify_message(message_bytes, output);
+        }
+
+        let message_js_array = Uint8Array::from(message_bytes);
+        let display = JsValue::from(display.as_str());
+
+        let public_key = self.public_key().unwrap_or_default();
+        let promise = match self.name {
+            Wallet::Phantom => {
+                phantom().and_then(|phantom| phantom.sign_message(&message_js_array, &display))
+            }
+            Wallet::Solflare => {
+                solflare().and_then(|solflare| solflare.sign_message(&message_js_array, &display))
+            }
+            Wallet::Backpack => backpack().and_then(|backpack| {
+                backpack.sign_message(
+                    &message_js_array,
+                    &JsValue::from(public_key),
+                    &JsValue::from("uuid"),
+                )
+            }),
+            Wallet::Standard(_) => return Err(WalletError::WalletConfigError),
         };
 
-        let result = JsFuture::from(promise).await;
+        let result = self
+            .cancel
+            .run(self.timeouts().sign, resolve(promise))
+            .await?;
 
         match result {
-            Ok(json_str) => {
-                let deserialized: JsSignatureObject = JsValue::into_serde(&json_str).unwrap();
+            Ok(response) => {
+                let signature = match response.dyn_ref::<Uint8Array>() {
+                    Some(signature) => Some(signature.to_vec()),
+                    None => from_js_json::<JsSignatureResponse>(&response)
+                        .and_then(|response| response.signature.to_vec()),
+                };
+                let signature = signature
+                    .and_then(|signature| Signature::try_from(signature).ok())
+                    .ok_or(WalletError::WalletSignMessageError)?;
+                info!("Message signed: {:?}", signature);
 
-                let signature = Signature::from_str(&deserialized.signature).unwrap();
-                info!("Got signature: {:?}", signature);
+                self.verify_message(
+                    message_bytes,
+                    SignMessageOutput {
+                        signed_message: message_bytes.to_vec(),
+                        signature,
+                    },
+                )
+            }
+            Err(err) => {
+                log::error!("Failed to sign message: {:?}", err);
+                Err(WalletError::from_js(WalletOperation::SignMessage, &err))
+            }
+        }
+    }
 
-                Ok(signature)
+    /// Signs the user in with Sign In With Solana, which connects and proves ownership of the
+    /// account in one prompt.
+    ///
+    /// Uses the wallet's native `signIn` when it has one. Otherwise connects if needed, builds
+    /// the SIWS message from `input` and signs it with `signMessage`.
+    pub async fn sign_in(&mut self, input: SignInInput) -> Result<SignInOutput, WalletError> {
+        info!("Signing in...");
+        let _request = self.request(self.timeouts().sign).await?;
+
+        let js_input =
+            serde_wasm_bindgen::to_value(&input).map_err(|_| WalletError::WalletSignInError)?;
+
+        let timeout = self.timeouts().sign;
+        let native = match self.name {
+            Wallet::Phantom if has_method(phantom(), "signIn") => Some(
+                self.cancel
+                    .run(
+                        timeout,
+                        resolve(phantom().and_then(|phantom| phantom.sign_in(&js_input))),
+                    )
+                    .await,
+            ),
+            Wallet::Backpack if has_method(backpack(), "signIn") => Some(
+                self.cancel
+                    .run(
+                        timeout,
+                        resolve(backpack().and_then(|backpack| backpack.sign_in(&js_input))),
+                    )
+                    .await,
+            ),
+            Wallet::Standard(ref name) if self.features().iter().any(|f| f == SOLANA_SIGN_IN) => {
+                Some(
+                    self.cancel
+                        .run(timeout, standard::sign_in(name, &js_input))
+                        .await,
+                )
+            }
+            _ => None,
+        };
+
+        let result = match native {
+            Some(response) => response.and_then(sign_in_output).and_then(|output| {
+                self.verify_sign_in(&input, &output)?;
+                Ok(output)
+            }),
+            // Checked against the message it was built from when it was signed.
+            None => self.sign_in_with_message(input).await,
+        };
+
+        match result {
+            Ok(output) => {
+                info!("Signed in with public key: {:?}", output.account);
+                if self.public_key() != Some(output.account) {
+                    self.set_connected(output.account).await;
+                }
+                Ok(output)
             }
             Err(err) => {
-                log::error!("Failed to sign transaction: {:?}", err);
-                Err(WalletError::WalletSignTransactionError)
+                log::error!("Failed to sign in: {}", err);
+                self.emit_error(err.clone());
+                Err(err)
             }
         }
     }
 
-    async fn sign_message(&mut self, message: &str) -> Result<Signature, WalletError> {
-        info!("Signing transaction...");
+    async fn sign_in_with_message(
+        &mut self,
+        mut input: SignInInput,
+    ) -> Result<SignInOutput, WalletError> {
+        if self.public_key().is_none() {
+            self.connect_with(false).await?;
+        }
+        let account = self
+            .public_key()
+            .ok_or(WalletError::WalletNotConnectedError)?;
+
+        match input.address {
+            Some(ref address) if *address != account.to_string() => {
+                log::error!(
+                    "Sign in requested for {}, but {} is connected",
+                    address,
+                    account
+                );
+                return Err(WalletError::WalletAccountError);
+            }
+            Some(_) => {}
+            None => input.address = Some(account.to_string()),
+        }
+        if input.domain.is_none() {
+            input.domain = window().and_then(|window| window.location().host().ok());
+        }
 
-        if self.public_key.is_none() {
-            return Err(WalletError::WalletNotConnectedError);
+        let message = create_sign_in_message(&input).ok_or(WalletError::WalletSignInError)?;
+        let output = self
+            .sign_message_bytes(message.as_bytes(), MessageDisplay::default())
+            .await?;
+
+        Ok(SignInOutput {
+            account,
+            signed_message: output.signed_message,
+            signature: output.signature,
+        })
+    }
+}
+
+impl WalletAdapter for BaseWalletAdapter {
+    fn name(&self) -> String {
+        match self.name {
+            Wallet::Standard(ref name) => name.to_string(),
+            _ => format!("{:?}", self.name).to_string(),
         }
+    }
 
-        let message_bytes =
-            bincode::serialize(&message).map_err(|_| WalletError::WalletSignTransactionError)?;
+    fn url(&self) -> String {
+        self.url.to_string()
+    }
 
-        let message_js_array = Uint8Array::from(&message_bytes[..]);
+    fn icon(&self) -> String {
+        match self.name {
+            Wallet::Standard(ref name) => standard::find_wallet(name)
+                .map(|wallet| standard::wallet_icon(&wallet))
+                .unwrap_or_else(|| self.icon.to_string()),
+            _ => self.icon.to_string(),
+        }
+    }
 
-        let promise: Promise = match self.name {
-            Wallet::Phantom => SOLANA.sign_message(&message_js_array),
-            Wallet::Solflare => SOLFLARE.sign_message(&message_js_array),
-            Wallet::Backpack => BACKPACK.sign_message(
-                &message_js_array,
-                &JsValue::from(self.public_key.unwrap()),
-                &JsValue::from("uuid"),
-            ),
-        };
+    fn features(&self) -> Vec<String> {
+        match self.name {
+            Wallet::Standard(ref name) => standard::find_wallet(name)
+                .map(|wallet| standard::wallet_features(&wallet))
+                .unwrap_or_else(|| self.features.clone()),
+            _ => self.features.clone(),
+        }
+    }
 
-        let result = JsFuture::from(promise).await;
+    /// Checked on every call, so a wallet injected after the page loaded shows up as
+    /// [`WalletReadyState::Installed`].
+    fn ready_state(&self) -> WalletReadyState {
+        ready_state_of(&self.name)
+    }
 
-        match result {
-            Ok(json_str) => {
-                let sig_obj: JsSignatureResponse = JsValue::into_serde(&json_str).unwrap();
-                let data = sig_obj.signature.data;
+    fn public_key(&self) -> Option<Pubkey> {
+        self.session.public_key()
+    }
 
-                let signature = Signature::new(&data);
-                info!("Message signed: {:?}", signature);
+    fn connecting(&self) -> bool {
+        self.session.with(|state| state.connecting)
+    }
 
-                Ok(signature)
+    /// Restores a connection the user approved before, without a popup or a new tab.
+    ///
+    /// Resolves to `false` when the wallet is missing or does not trust the site yet.
+    async fn auto_connect(&mut self) -> Result<bool, WalletError> {
+        let _request = self.request(self.timeouts().connect).await?;
+        self.connect_with(true).await
+    }
+
+    /// Asks the user to approve the connection, opening the wallet's site when it is missing.
+    async fn connect(&mut self) -> Result<bool, WalletError> {
+        let _request = self.request(self.timeouts().connect).await?;
+        self.connect_with(false).await
+    }
+
+    async fn disconnect(&mut self) -> Result<bool, WalletError> {
+        info!("Disconnecting from wallet...");
+        let mut confirmed = false;
+
+        if self.public_key().is_none() {
+            self.emit_error(WalletError::WalletDisconnectedError);
+            return Err(WalletError::WalletDisconnectedError);
+        }
+
+        self.session.disconnect();
+        self.emit_disconnect();
+        let (cancel, timeout) = (self.cancel.clone(), self.timeouts().connect);
+        let disconnected = |promise| async move {
+            cancel
+                .run(timeout, resolve(promise))
+                .await?
+                .map(|_| ())
+                .map_err(|err| WalletError::from_js(WalletOperation::Disconnect, &err))
+        };
+        let result = match self.name {
+            Wallet::Phantom => {
+                disconnected(phantom().and_then(|phantom| phantom.disconnect())).await
+            }
+            Wallet::Solflare => {
+                disconnected(solflare().and_then(|solflare| solflare.disconnect())).await
+            }
+            Wallet::Backpack => {
+                disconnected(backpack().and_then(|backpack| backpack.disconnect())).await
+            }
+            Wallet::Standard(ref name) => self
+                .cancel
+                .run(self.timeouts().connect, standard::disconnect(name))
+                .await
+                .and_then(|result| result),
+        };
+
+        match result {
+            Ok(_) => {
+                confirmed = true;
+                info!("Disconnected from wallet");
             }
             Err(err) => {
-                log::error!("Failed to sign transaction: {:?}", err);
-                Err(WalletError::WalletSignTransactionError)
+                log::error!("Failed to disconnect wallet: {}", err);
+                self.emit_error(err);
             }
         }
+
+        Ok(confirmed)
+    }
+
+    async fn send_transaction_with_options(
+        &mut self,
+        client: Arc<RpcClient>,
+        transaction: TransactionOrVersionedTransaction,
+        options: SendOptions,
+    ) -> Result<Signature, WalletError> {
+        BaseWalletAdapter::send_transaction_with_options(self, client, transaction, options).await
     }
 }
 
 impl WalletAdapterEvents for BaseWalletAdapter {
+    fn subscribe(&self, listener: impl Fn(&WalletEvent) + 'static) -> Subscription {
+        let subscription = self.events.subscribe(listener);
+        self.watch_ready_state();
+        subscription
+    }
+
+    fn events(&self) -> EventStream {
+        let stream = self.events.stream();
+        self.watch_ready_state();
+        stream
+    }
+
     fn emit_connect(&mut self, public_key: Pubkey) {
-        self.emitter.emit("connect", public_key);
+        self.events.emit(WalletEvent::Connect(public_key));
     }
 
     fn emit_disconnect(&mut self) {
-        self.emitter.emit("disconnect", ());
+        self.events.emit(WalletEvent::Disconnect);
     }
 
     fn emit_error(&mut self, error: WalletError) {
-        self.emitter.emit("error", error);
+        self.events.emit(WalletEvent::Error(error));
     }
 
     fn ready_state_change(&mut self, ready_state: WalletReadyState) {
-        self.emitter.emit("ready_state_change", ready_state);
+        self.events.emit(WalletEvent::ReadyStateChange(ready_state));
     }
 
     fn emit_transaction_sent(&mut self, signature: Signature) {
-        self.emitter.emit("transaction_sent", signature);
+        self.events.emit(WalletEvent::TransactionSent(signature));
+    }
+}
+
+fn ready_state_of(wallet: &Wallet) -> WalletReadyState {
+    if !cfg!(target_arch = "wasm32") {
+        return WalletReadyState::NotDetected;
+    }
+    if window().is_none() {
+        return WalletReadyState::Unsupported;
+    }
+
+    let injected = match wallet {
+        Wallet::Phantom => phantom().is_ok(),
+        Wallet::Solflare => solflare().is_ok(),
+        Wallet::Backpack => backpack().is_ok(),
+        Wallet::Standard(name) => standard::find_wallet(name).is_some(),
+    };
+
+    match wallet {
+        _ if injected => WalletReadyState::Installed,
+        Wallet::Phantom | Wallet::Solflare if is_mobile() => WalletReadyState::Loadable,
+        _ => WalletReadyState::NotDetected,
+    }
+}
+
+/// Sends `transaction` through `client` and waits until it reaches the client's commitment,
+/// failing if it landed but failed.
+async fn send_and_confirm(
+    client: &RpcClient,
+    session: &Session,
+    events: &WalletEvents,
+    transaction: &TransactionOrVersionedTransaction,
+    options: SendOptions,
+) -> Result<Signature, WalletError> {
+    let signature = client
+        .send_with_config(transaction, client.send_config(&options))
+        .await
+        .map_err(|err| {
+            log::error!("Failed to send transaction: {:?}", err);
+            WalletError::from_rpc(WalletOperation::SendTransaction, &err)
+        })?;
+
+    let expiry = session.expiry(*transaction.recent_blockhash());
+    confirmation::confirm(
+        client,
+        events,
+        signature,
+        client.commitment_config(),
+        expiry,
+    )
+    .await?
+    .into_result()?;
+    Ok(signature)
+}
+
+/// Sends already signed transactions through `client`, keeping their order in the results.
+async fn send_all(
+    client: &RpcClient,
+    session: &Session,
+    events: &WalletEvents,
+    signed: Vec<Result<TransactionOrVersionedTransaction, WalletError>>,
+    mode: SendMode,
+    options: SendOptions,
+) -> Vec<Result<Signature, WalletError>> {
+    match mode {
+        SendMode::Parallel => {
+            join_all(signed.into_iter().map(|transaction| async move {
+                send_and_confirm(client, session, events, &transaction?, options).await
+            }))
+            .await
+        }
+        SendMode::Sequential => {
+            let mut results = Vec::with_capacity(signed.len());
+            for transaction in signed {
+                let result = match (results.iter().any(Result::is_err), transaction) {
+                    // Not sent, as an earlier transaction was not sent or failed on chain.
+                    (true, _) => Err(WalletError::WalletSendTransactionError),
+                    (false, Ok(transaction)) => {
+                        send_and_confirm(client, session, events, &transaction, options).await
+                    }
+                    (false, Err(err)) => Err(err),
+                };
+                results.push(result);
+            }
+            results
+        }
+    }
+}
+
+/// Records the accounts the wallet exposes and the active one, emitting what changed.
+///
+/// Without an `active` account, the current one is kept if the wallet still exposes it.
+fn update_accounts(
+    session: &Session,
+    events: &WalletEvents,
+    mut accounts: Vec<Pubkey>,
+    active: Option<Pubkey>,
+) {
+    let (accounts_changed, previous, current) = session.with(|state| {
+        let current = active
+            .or_else(|| state.public_key.filter(|key| accounts.contains(key)))
+            .or_else(|| accounts.first().copied())
+            .or(state.public_key);
+        if let Some(current) = current.filter(|key| !accounts.contains(key)) {
+            accounts.insert(0, current);
+        }
+
+        let accounts_changed = state.accounts != accounts;
+        state.accounts.clone_from(&accounts);
+        let previous = std::mem::replace(&mut state.public_key, current);
+        (accounts_changed, previous, current)
+    });
+
+    if accounts_changed {
+        events.emit(WalletEvent::AccountsChanged(accounts));
+    }
+    if let (Some(previous), Some(current)) = (previous, current) {
+        if previous != current {
+            info!("Switched to account {}", current);
+            events.emit(WalletEvent::AccountChanged(current));
+        }
+    }
+}
+
+/// Reads the accounts `_backpackGetAccounts` resolves with, given as keys or as objects with a
+/// `publicKey`.
+fn backpack_accounts(response: &JsValue) -> Vec<Pubkey> {
+    response
+        .dyn_ref::<Array>()
+        .map(|accounts| {
+            accounts
+                .iter()
+                .filter_map(|account| {
+                    js_public_key(&account).or_else(|| {
+                        js_sys::Reflect::get(&account, &JsValue::from("publicKey"))
+                            .ok()
+                            .and_then(|key| js_public_key(&key))
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parses a `signIn` reply: Phantom and Backpack name the account `address`, Wallet Standard
+/// wallets return it as `account`.
+fn sign_in_output(response: Result<JsValue, JsValue>) -> Result<SignInOutput, WalletError> {
+    let response = response.map_err(|err| {
+        log::error!("Failed to sign in: {:?}", err);
+        WalletError::from_js(WalletOperation::SignIn, &err)
+    })?;
+    let field = |key: &str| {
+        js_sys::Reflect::get(&response, &JsValue::from(key))
+            .ok()
+            .filter(|value| !value.is_undefined())
+    };
+    let bytes = |key: &str| {
+        field(key)
+            .and_then(|value| value.dyn_into::<Uint8Array>().ok())
+            .map(|value| value.to_vec())
+            .ok_or(WalletError::WalletSignInError)
+    };
+
+    let account = field("account")
+        .map(|account| js_sys::Reflect::get(&account, &JsValue::from("address")).unwrap_or(account))
+        .or_else(|| field("address"))
+        .and_then(|account| js_public_key(&account))
+        .ok_or(WalletError::WalletPublicKeyError)?;
+    let signature =
+        Signature::try_from(bytes("signature")?).map_err(|_| WalletError::WalletSignInError)?;
+
+    Ok(SignInOutput {
+        account,
+        signed_message: bytes("signedMessage")?,
+        signature,
+    })
+}
+
+/// Reads a public key given either as a base58 string or as a web3.js `PublicKey`.
+pub(crate) fn js_public_key(value: &JsValue) -> Option<Pubkey> {
+    let address = match value.as_string() {
+        Some(address) => address,
+        None => js_sys::Reflect::get(value, &JsValue::from("toString"))
+            .ok()?
+            .dyn_into::<Function>()
+            .ok()?
+            .call0(value)
+            .ok()?
+            .as_string()?,
+    };
+    Pubkey::from_str(&address).ok()
+}
+
+/// Awaits a provider call, whether it threw or its promise rejected.
+async fn resolve(promise: Result<Promise, JsValue>) -> Result<JsValue, JsValue> {
+    JsFuture::from(promise?).await
+}
+
+pub(crate) fn backpack() -> Result<Backpack, JsValue> {
+    injected(&["xnft", "solana"])
+}
+
+pub(crate) fn phantom() -> Result<Solana, JsValue> {
+    injected(&["solana"])
+}
+
+pub(crate) fn solflare() -> Result<Solflare, JsValue> {
+    injected(&["solflare"])
+}
+
+/// Looks a provider up on `window` on every call, as wallets may inject it after the page
+/// has loaded.
+fn injected<T: JsCast>(path: &[&str]) -> Result<T, JsValue> {
+    let mut provider: JsValue = window()
+        .ok_or_else(|| JsValue::from_str("No window"))?
+        .into();
+    for key in path {
+        provider = js_sys::Reflect::get(&provider, &JsValue::from_str(key))?;
+        if !provider.is_object() {
+            return Err(JsValue::from_str(&format!(
+                "window.{} is not available",
+                path.join(".")
+            )));
+        }
+    }
+    Ok(provider.unchecked_into())
+}
+
+fn has_method(provider: Result<impl AsRef<JsValue>, JsValue>, method: &str) -> bool {
+    provider.is_ok_and(|provider| {
+        js_sys::Reflect::has(provider.as_ref(), &JsValue::from(method)).unwrap_or(false)
+    })
+}
+
+fn is_mobile() -> bool {
+    window()
+        .and_then(|window| window.navigator().user_agent().ok())
+        .is_some_and(|user_agent| {
+            let user_agent = user_agent.to_lowercase();
+            ["android", "iphone", "ipad", "ipod"]
+                .iter()
+                .any(|device| user_agent.contains(device))
+        })
+}
+
+/// Builds the `{ method, params: { message } }` argument of a provider's `request`.
+fn request_options(method: &str, message: &JsValue) -> Result<js_sys::Object, JsValue> {
+    let params = js_sys::Object::new();
+    js_sys::Reflect::set(&params, &JsValue::from("message"), message)?;
+
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &JsValue::from("method"), &JsValue::from(method))?;
+    js_sys::Reflect::set(&options, &JsValue::from("params"), &params)?;
+    Ok(options)
+}
+
+/// Deserializes a provider reply through `JSON.stringify`, which fails instead of throwing on
+/// values that can't be serialized.
+fn from_js_json<T: serde::de::DeserializeOwned>(value: &JsValue) -> Option<T> {
+    let json = js_sys::JSON::stringify(value).ok()?.as_string()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Builds the signed transaction from a wallet's `signTransaction` reply, which is either the
+/// serialized transaction, a web3.js transaction object or just its signatures.
+fn signed_transaction(
+    unsigned: &TransactionOrVersionedTransaction,
+    response: &JsValue,
+    public_key: Option<Pubkey>,
+) -> Result<TransactionOrVersionedTransaction, WalletError> {
+    if let Some(bytes) = response.dyn_ref::<Uint8Array>() {
+        return signed_transaction_from_bytes(unsigned, &bytes.to_vec());
+    }
+
+    let serialize = js_sys::Reflect::get(response, &JsValue::from("serialize"))
+        .ok()
+        .and_then(|serialize| serialize.dyn_into::<Function>().ok());
+    if let Some(serialize) = serialize {
+        let config = js_sys::Object::new();
+        for key in ["requireAllSignatures", "verifySignatures"] {
+            js_sys::Reflect::set(&config, &JsValue::from(key), &JsValue::FALSE)
+                .map_err(|_| WalletError::WalletSignTransactionError)?;
+        }
+        let bytes = serialize
+            .call1(response, &config)
+            .ok()
+            .and_then(|bytes| bytes.dyn_into::<Uint8Array>().ok())
+            .ok_or(WalletError::WalletSignTransactionError)?;
+        return signed_transaction_from_bytes(unsigned, &bytes.to_vec());
+    }
+
+    let response: SignedTransactionResponse = from_js_json(response).ok_or_else(|| {
+        log::error!("Unexpected signTransaction response: {:?}", response);
+        WalletError::WalletSignTransactionError
+    })?;
+
+    let mut signed = unsigned.clone();
+    let mut placed = false;
+    match response {
+        SignedTransactionResponse::Signatures { signatures } => {
+            for (index, entry) in signatures.iter().enumerate() {
+                let (index, signature) = match entry {
+                    TransactionSignature::Bytes(signature) => (Some(index), Some(signature)),
+                    TransactionSignature::WithPublicKey {
+                        signature,
+                        public_key,
+                    } => (
+                        Pubkey::from_str(public_key)
+                            .ok()
+                            .and_then(|public_key| signed.signer_index(&public_key)),
+                        signature.as_ref(),
+                    ),
+                };
+                let (Some(index), Some(signature)) = (index, signature) else {
+                    continue;
+                };
+                let signature = signature_from_js(signature)?;
+                if signature != Signature::default() {
+                    placed |= signed.set_signature(index, signature);
+                }
+            }
+        }
+        SignedTransactionResponse::Signature {
+            signature,
+            public_key: signer,
+        } => {
+            let signer = match signer {
+                Some(signer) => Pubkey::from_str(&signer).ok(),
+                None => public_key,
+            };
+            let index = signer
+                .and_then(|signer| signed.signer_index(&signer))
+                .ok_or(WalletError::WalletSignTransactionError)?;
+            placed = signed.set_signature(index, signature_from_js(&signature)?);
+        }
+    }
+
+    if !placed {
+        log::error!("Wallet returned no signature for this transaction");
+        return Err(WalletError::WalletSignTransactionError);
+    }
+    Ok(signed)
+}
+
+/// Deserializes a signed transaction and makes sure the wallet signed the message we sent.
+fn signed_transaction_from_bytes(
+    unsigned: &TransactionOrVersionedTransaction,
+    bytes: &[u8],
+) -> Result<TransactionOrVersionedTransaction, WalletError> {
+    let signed = unsigned
+        .from_bytes_like(bytes)
+        .map_err(|_| WalletError::WalletSignTransactionError)?;
+
+    if signed.message_data() != unsigned.message_data() {
+        log::error!("Wallet signed a different message than the one it was sent");
+        return Err(WalletError::WalletSignTransactionError);
+    }
+    Ok(signed)
+}
+
+fn signature_from_js(signature: &JsBytes) -> Result<Signature, WalletError> {
+    signature
+        .to_vec()
+        .and_then(|bytes| Signature::try_from(bytes).ok())
+        .ok_or(WalletError::WalletSignTransactionError)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use solana_sdk::{system_instruction, transaction::Transaction};
+
+    use super::*;
+
+    /// A Backpack adapter and the events it emits, without polling its ready state.
+    fn adapter() -> (
+        BaseWalletAdapter,
+        Subscription,
+        Rc<RefCell<Vec<WalletEvent>>>,
+    ) {
+        let adapter: BaseWalletAdapter = Wallet::Backpack.into();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let listener = events.clone();
+        let subscription = adapter
+            .events
+            .subscribe(move |event| listener.borrow_mut().push(event.clone()));
+        (adapter, subscription, events)
+    }
+
+    #[test]
+    fn the_first_account_is_active_until_another_is_selected() {
+        let (adapter, _subscription, events) = adapter();
+        let [first, second] = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        update_accounts(&adapter.session, &adapter.events, vec![first, second], None);
+        assert_eq!(adapter.public_key(), Some(first));
+        assert_eq!(adapter.accounts(), vec![first, second]);
+
+        assert_eq!(adapter.select_account(second), Ok(()));
+        assert_eq!(adapter.public_key(), Some(second));
+        assert_eq!(
+            adapter.select_account(Pubkey::new_unique()),
+            Err(WalletError::WalletAccountError)
+        );
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                WalletEvent::AccountsChanged(vec![first, second]),
+                WalletEvent::AccountChanged(second),
+            ]
+        );
+    }
+
+    #[test]
+    fn the_active_account_is_kept_while_the_wallet_exposes_it() {
+        let (adapter, _subscription, events) = adapter();
+        let [first, second, third] = [(); 3].map(|_| Pubkey::new_unique());
+        update_accounts(&adapter.session, &adapter.events, vec![first, second], None);
+        adapter.select_account(second).unwrap();
+        events.borrow_mut().clear();
+
+        update_accounts(&adapter.session, &adapter.events, vec![third, second], None);
+        assert_eq!(adapter.public_key(), Some(second));
+
+        update_accounts(&adapter.session, &adapter.events, vec![third], None);
+        assert_eq!(adapter.public_key(), Some(third));
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                WalletEvent::AccountsChanged(vec![third, second]),
+                WalletEvent::AccountsChanged(vec![third]),
+                WalletEvent::AccountChanged(third),
+            ]
+        );
+    }
+
+    #[test]
+    fn accounts_cannot_be_selected_before_connecting() {
+        let (adapter, _subscription, events) = adapter();
+
+        assert_eq!(
+            adapter.select_account(Pubkey::new_unique()),
+            Err(WalletError::WalletNotConnectedError)
+        );
+        assert!(events.borrow().is_empty());
+    }
+
+    fn transfer(payer: &Pubkey, lamports: u64) -> TransactionOrVersionedTransaction {
+        let transfer = system_instruction::transfer(payer, &Pubkey::new_unique(), lamports);
+        Transaction::new_with_payer(&[transfer], Some(payer)).into()
+    }
+
+    #[test]
+    fn takes_the_signed_transaction_the_wallet_serialized() {
+        let payer = Pubkey::new_unique();
+        let unsigned = transfer(&payer, 1);
+        let mut signed = unsigned.clone();
+        signed.set_signature(0, Signature::from([7; 64]));
+
+        assert_eq!(
+            signed_transaction_from_bytes(&unsigned, &signed.to_bytes().unwrap()),
+            Ok(signed)
+        );
+    }
+
+    #[test]
+    fn rejects_signed_transactions_for_another_message() {
+        let payer = Pubkey::new_unique();
+        let unsigned = transfer(&payer, 1);
+        let other = transfer(&payer, 2);
+
+        assert_eq!(
+            signed_transaction_from_bytes(&unsigned, &other.to_bytes().unwrap()),
+            Err(WalletError::WalletSignTransactionError)
+        );
+        assert_eq!(
+            signed_transaction_from_bytes(&unsigned, &[]),
+            Err(WalletError::WalletSignTransactionError)
+        );
     }
 }
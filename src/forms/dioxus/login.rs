@@ -1,7 +1,52 @@
+use std::rc::Rc;
+
 use dioxus::prelude::*;
 
-use crate::core::traits::WalletAdapter;
-use crate::core::wallet::BaseWalletAdapter;
+use crate::core::events::WalletEvent;
+use crate::core::traits::{WalletAdapter, WalletAdapterEvents};
+use crate::core::wallet::{BaseWalletAdapter, WalletReadyState};
+
+/// The wallet's ready state, updated when the wallet is injected after the page loads.
+fn use_ready_state(
+    adapter: Signal<BaseWalletAdapter, UnsyncStorage>,
+) -> Signal<WalletReadyState, UnsyncStorage> {
+    let ready_state = use_signal(|| adapter.peek().ready_state());
+    use_hook(|| {
+        Rc::new(adapter.peek().subscribe(move |event| {
+            if let WalletEvent::ReadyStateChange(current) = event {
+                let mut ready_state = ready_state;
+                ready_state.set(*current);
+            }
+        }))
+    });
+    ready_state
+}
+
+/// Whether the wallet is busy with a request.
+fn use_pending_request(
+    adapter: Signal<BaseWalletAdapter, UnsyncStorage>,
+) -> Signal<bool, UnsyncStorage> {
+    let pending = use_signal(|| adapter.peek().pending_request());
+    use_hook(|| {
+        Rc::new(adapter.peek().subscribe(move |event| {
+            if let WalletEvent::PendingRequestChange(current) = event {
+                let mut pending = pending;
+                pending.set(*current);
+            }
+        }))
+    });
+    pending
+}
+
+fn connect_label(ready_state: WalletReadyState, wallet: &str) -> String {
+    match ready_state {
+        WalletReadyState::Installed => format!("Connect {} Wallet", wallet),
+        WalletReadyState::Loadable => format!("Open in {} Wallet", wallet),
+        WalletReadyState::NotDetected | WalletReadyState::Unsupported => {
+            format!("Install {} Wallet", wallet)
+        }
+    }
+}
 
 #[derive(Props, Clone, PartialEq)]
 pub struct CompProps {
@@ -23,16 +68,23 @@ pub fn LoginForm(props: CompProps) -> Element {
     let mut solflare_wallet_info = use_signal(|| BaseWalletAdapter::default());
     let mut backpack_wallet_info = use_signal(|| BaseWalletAdapter::default());
 
-    if phantom_wallet.is_some() {
-        phantom_wallet_info = phantom_wallet.unwrap();
+    if let Some(phantom_wallet) = phantom_wallet {
+        phantom_wallet_info = phantom_wallet;
     }
-    if solflare_wallet.is_some() {
-        solflare_wallet_info = solflare_wallet.unwrap();
+    if let Some(solflare_wallet) = solflare_wallet {
+        solflare_wallet_info = solflare_wallet;
     }
-    if backpack_wallet.is_some() {
-        backpack_wallet_info = backpack_wallet.unwrap();
+    if let Some(backpack_wallet) = backpack_wallet {
+        backpack_wallet_info = backpack_wallet;
     }
 
+    let phantom_ready_state = use_ready_state(phantom_wallet_info);
+    let solflare_ready_state = use_ready_state(solflare_wallet_info);
+    let backpack_ready_state = use_ready_state(backpack_wallet_info);
+    let phantom_pending = use_pending_request(phantom_wallet_info);
+    let solflare_pending = use_pending_request(solflare_wallet_info);
+    let backpack_pending = use_pending_request(backpack_wallet_info);
+
     let error = use_signal(|| None as Option<String>);
 
     let connect_wallet_phantom = move |_| {
@@ -41,9 +93,6 @@ pub fn LoginForm(props: CompProps) -> Element {
 
             match phantom_wallet_adapter.connect().await {
                 Ok(confirmed) => {
-                    if confirmed {
-                        phantom_wallet_info.set(phantom_wallet_adapter);
-                    }
                     connected.set(confirmed);
                 }
                 Err(err) => {
@@ -59,9 +108,6 @@ pub fn LoginForm(props: CompProps) -> Element {
 
             match solflare_wallet_adapter.connect().await {
                 Ok(confirmed) => {
-                    if confirmed {
-                        solflare_wallet_info.set(solflare_wallet_adapter);
-                    }
                     connected.set(confirmed);
                 }
                 Err(err) => {
@@ -77,9 +123,6 @@ pub fn LoginForm(props: CompProps) -> Element {
 
             match backpack_wallet_adapter.connect().await {
                 Ok(confirmed) => {
-                    if confirmed {
-                        backpack_wallet_info.set(backpack_wallet_adapter);
-                    }
                     connected.set(confirmed);
                 }
                 Err(err) => {
@@ -97,7 +140,6 @@ pub fn LoginForm(props: CompProps) -> Element {
 
             match phantom_wallet_adapter.disconnect().await {
                 Ok(_) => {
-                    phantom_wallet_info.set(phantom_wallet_adapter);
                     connected.set(false);
                 }
                 Err(_err) => {}
@@ -105,7 +147,6 @@ pub fn LoginForm(props: CompProps) -> Element {
 
             match solflare_wallet_adapter.disconnect().await {
                 Ok(_) => {
-                    solflare_wallet_info.set(solflare_wallet_adapter);
                     connected.set(false);
                 }
                 Err(_err) => {}
@@ -113,7 +154,6 @@ pub fn LoginForm(props: CompProps) -> Element {
 
             match backpack_wallet_adapter.disconnect().await {
                 Ok(_) => {
-                    backpack_wallet_info.set(backpack_wallet_adapter);
                     connected.set(false);
                 }
                 Err(_err) => {}
@@ -131,36 +171,39 @@ pub fn LoginForm(props: CompProps) -> Element {
                         button {
                             class: "connect-button-phantom",
                             onclick: connect_wallet_phantom,
+                            disabled: phantom_pending(),
                             img {
                                 src: phantom_wallet_info().icon(),
                                 alt: "Phantom Wallet",
                                 class: "button-icon-phantom"
                             },
-                            "Connect Phantom Wallet"
+                            {connect_label(phantom_ready_state(), "Phantom")}
                         }
                     }
                     if solflare_wallet.is_some() {
                         button {
                             class: "connect-button-solflare",
                             onclick: connect_wallet_solflare,
+                            disabled: solflare_pending(),
                             img {
                                 src: solflare_wallet_info().icon(),
                                 alt: "Solflare Wallet",
                                 class: "button-icon-solflare"
                             },
-                            "Connect Solflare Wallet"
+                            {connect_label(solflare_ready_state(), "Solflare")}
                         }
                     }
                     if backpack_wallet.is_some() {
                         button {
                             class: "connect-button-backpack",
                             onclick: connect_wallet_backpack,
+                            disabled: backpack_pending(),
                             img {
                                 src: backpack_wallet_info().icon(),
                                 alt: "Backpack Wallet",
                                 class: "button-icon-backpack"
                             },
-                            "Connect Backpack Wallet"
+                            {connect_label(backpack_ready_state(), "Backpack")}
                         }
                     }
                 } else if let Some(ref _key) = phantom_wallet_info().public_key() {
@@ -1,10 +1,46 @@
 use leptos::*;
 
-use crate::core::traits::WalletAdapter;
-use crate::core::wallet::BaseWalletAdapter;
-use crate::pubkey::Pubkey;
+use crate::core::events::WalletEvent;
+use crate::core::traits::{WalletAdapter, WalletAdapterEvents};
+use crate::core::wallet::{BaseWalletAdapter, WalletReadyState};
 use wasm_bindgen_futures::spawn_local;
 
+/// The wallet's ready state, updated when the wallet is injected after the page loads.
+fn ready_state_signal(adapter: ReadSignal<BaseWalletAdapter>) -> ReadSignal<WalletReadyState> {
+    let adapter = adapter.get_untracked();
+    let (ready_state, set_ready_state) = create_signal(adapter.ready_state());
+    let subscription = adapter.subscribe(move |event| {
+        if let WalletEvent::ReadyStateChange(current) = event {
+            set_ready_state.set(*current);
+        }
+    });
+    on_cleanup(move || drop(subscription));
+    ready_state
+}
+
+/// Whether the wallet is busy with a request.
+fn pending_request_signal(adapter: ReadSignal<BaseWalletAdapter>) -> ReadSignal<bool> {
+    let adapter = adapter.get_untracked();
+    let (pending, set_pending) = create_signal(adapter.pending_request());
+    let subscription = adapter.subscribe(move |event| {
+        if let WalletEvent::PendingRequestChange(current) = event {
+            set_pending.set(*current);
+        }
+    });
+    on_cleanup(move || drop(subscription));
+    pending
+}
+
+fn connect_label(ready_state: WalletReadyState, wallet: &str) -> String {
+    match ready_state {
+        WalletReadyState::Installed => format!("Connect {} Wallet", wallet),
+        WalletReadyState::Loadable => format!("Open in {} Wallet", wallet),
+        WalletReadyState::NotDetected | WalletReadyState::Unsupported => {
+            format!("Install {} Wallet", wallet)
+        }
+    }
+}
+
 #[component]
 pub fn LoginForm(
     phantom: Option<(
@@ -23,40 +59,41 @@ pub fn LoginForm(
 ) -> impl IntoView {
     let (connected, set_connected) = connected;
 
-    let (mut phantom_wallet_adapter, mut set_phantom_wallet_adapter) =
-        create_signal(BaseWalletAdapter::default());
-    let (mut solflare_wallet_adapter, mut set_solflare_wallet_adapter) =
-        create_signal(BaseWalletAdapter::default());
-    let (mut backpack_wallet_adapter, mut set_backpack_wallet_adapter) =
-        create_signal(BaseWalletAdapter::default());
+    let mut phantom_wallet_adapter = create_signal(BaseWalletAdapter::default()).0;
+    let mut solflare_wallet_adapter = create_signal(BaseWalletAdapter::default()).0;
+    let mut backpack_wallet_adapter = create_signal(BaseWalletAdapter::default()).0;
 
-    if phantom.is_some() {
-        (phantom_wallet_adapter, set_phantom_wallet_adapter) = phantom.unwrap();
+    if let Some(phantom) = phantom {
+        (phantom_wallet_adapter, _) = phantom;
     }
-    if solflare.is_some() {
-        (solflare_wallet_adapter, set_solflare_wallet_adapter) = solflare.unwrap();
+    if let Some(solflare) = solflare {
+        (solflare_wallet_adapter, _) = solflare;
     }
-    if backpack.is_some() {
-        (backpack_wallet_adapter, set_backpack_wallet_adapter) = backpack.clone().unwrap();
+    if let Some(backpack) = backpack {
+        (backpack_wallet_adapter, _) = backpack;
     }
 
+    let phantom_ready_state = ready_state_signal(phantom_wallet_adapter);
+    let solflare_ready_state = ready_state_signal(solflare_wallet_adapter);
+    let backpack_ready_state = ready_state_signal(backpack_wallet_adapter);
+    let phantom_pending = pending_request_signal(phantom_wallet_adapter);
+    let solflare_pending = pending_request_signal(solflare_wallet_adapter);
+    let backpack_pending = pending_request_signal(backpack_wallet_adapter);
+
     let (error, set_error) = create_signal(String::default());
 
     let connect_phantom_wallet = move |_| {
         spawn_local(async move {
             let mut wallet_info = phantom_wallet_adapter.get();
 
-            wallet_info
-                .emitter
-                .on("connect", move |public_key: Pubkey| {
+            let _subscription = wallet_info.subscribe(|event| {
+                if let WalletEvent::Connect(public_key) = event {
                     log::info!("Event Listener: Got pubkey {}", public_key);
-                });
+                }
+            });
 
             match wallet_info.connect().await {
                 Ok(confirmed) => {
-                    if confirmed {
-                        set_phantom_wallet_adapter.set(wallet_info);
-                    }
                     set_connected.set(confirmed);
                 }
                 Err(err) => {
@@ -71,17 +108,14 @@ pub fn LoginForm(
         spawn_local(async move {
             let mut wallet_info = solflare_wallet_adapter.get();
 
-            wallet_info
-                .emitter
-                .on("connect", move |public_key: Pubkey| {
+            let _subscription = wallet_info.subscribe(|event| {
+                if let WalletEvent::Connect(public_key) = event {
                     log::info!("Event Listener: Got pubkey {}", public_key);
-                });
+                }
+            });
 
             match wallet_info.connect().await {
                 Ok(confirmed) => {
-                    if confirmed {
-                        set_phantom_wallet_adapter.set(wallet_info);
-                    }
                     set_connected.set(confirmed);
                 }
                 Err(err) => {
@@ -96,17 +130,14 @@ pub fn LoginForm(
         spawn_local(async move {
             let mut wallet_info = backpack_wallet_adapter.get();
 
-            wallet_info
-                .emitter
-                .on("connect", move |public_key: Pubkey| {
+            let _subscription = wallet_info.subscribe(|event| {
+                if let WalletEvent::Connect(public_key) = event {
                     log::info!("Event Listener: Got pubkey {}", public_key);
-                });
+                }
+            });
 
             match wallet_info.connect().await {
                 Ok(confirmed) => {
-                    if confirmed {
-                        set_phantom_wallet_adapter.set(wallet_info);
-                    }
                     set_connected.set(confirmed);
                 }
                 Err(err) => {
@@ -125,27 +156,18 @@ pub fn LoginForm(
 
             match phantom_wallet_info.disconnect().await {
                 Ok(confirmed) => {
-                    if confirmed {
-                        set_phantom_wallet_adapter.set(phantom_wallet_info);
-                    }
                     set_connected.set(!confirmed);
                 }
                 Err(_err) => {}
             }
             match solflare_wallet_info.disconnect().await {
                 Ok(confirmed) => {
-                    if confirmed {
-                        set_solflare_wallet_adapter.set(solflare_wallet_info);
-                    }
                     set_connected.set(!confirmed);
                 }
                 Err(_err) => {}
             }
             match backpack_wallet_info.disconnect().await {
                 Ok(confirmed) => {
-                    if confirmed {
-                        set_backpack_wallet_adapter.set(backpack_wallet_info);
-                    }
                     set_connected.set(!confirmed);
                 }
                 Err(_err) => {}
@@ -159,17 +181,17 @@ pub fn LoginForm(
                 {move ||
                     if !connected.get() {
                         view!{
-                            <button class="connect-button-phantom" on:click=connect_phantom_wallet>
+                            <button class="connect-button-phantom" on:click=connect_phantom_wallet disabled=phantom_pending>
                                 <img src={phantom_wallet_adapter.get().icon()} alt="Phantom Wallet" class="button-icon" />
-                                "Connect Phantom Wallet"
+                                {connect_label(phantom_ready_state.get(), "Phantom")}
                             </button>
-                            <button class="connect-button-solflare" on:click=connect_solflare_wallet>
+                            <button class="connect-button-solflare" on:click=connect_solflare_wallet disabled=solflare_pending>
                                 <img src={solflare_wallet_adapter.get().icon()} alt="Solflare Wallet" class="button-icon" />
-                                "Connect Solflare Wallet"
+                                {connect_label(solflare_ready_state.get(), "Solflare")}
                             </button>
-                            <button class="connect-button-backpack" on:click=connect_backpack_wallet>
+                            <button class="connect-button-backpack" on:click=connect_backpack_wallet disabled=backpack_pending>
                                 <img src={backpack_wallet_adapter.get().icon()} alt="Backpack Wallet" class="button-icon" />
-                                "Connect Backpack Wallet"
+                                {connect_label(backpack_ready_state.get(), "Backpack")}
                             </button>
                         }
                     } else if let Some(_key) = phantom_wallet_adapter.get().public_key() {
@@ -1,10 +1,61 @@
 use yew::prelude::*;
 
-use crate::core::traits::WalletAdapter;
-use crate::core::wallet::BaseWalletAdapter;
-use crate::pubkey::Pubkey;
+use crate::core::events::WalletEvent;
+use crate::core::traits::{WalletAdapter, WalletAdapterEvents};
+use crate::core::wallet::{BaseWalletAdapter, WalletReadyState};
 use wasm_bindgen_futures::spawn_local;
 
+/// The wallet's ready state, re-rendering when the wallet is injected after the page loads.
+#[hook]
+fn use_ready_state(adapter: &UseStateHandle<BaseWalletAdapter>) -> WalletReadyState {
+    let ready_state = use_state(|| adapter.ready_state());
+    {
+        let ready_state = ready_state.clone();
+        use_effect_with((**adapter).clone(), move |adapter| {
+            let subscription = {
+                let ready_state = ready_state.clone();
+                adapter.subscribe(move |event| {
+                    if let WalletEvent::ReadyStateChange(current) = event {
+                        ready_state.set(*current);
+                    }
+                })
+            };
+            // The wallet may have been injected before the adapter was watched.
+            ready_state.set(adapter.ready_state());
+            move || drop(subscription)
+        });
+    }
+    *ready_state
+}
+
+/// Whether the wallet is busy with a request, re-rendering when that changes.
+#[hook]
+fn use_pending_request(adapter: &UseStateHandle<BaseWalletAdapter>) -> bool {
+    let pending = use_state(|| adapter.pending_request());
+    {
+        let pending = pending.clone();
+        use_effect_with((**adapter).clone(), move |adapter| {
+            let subscription = adapter.subscribe(move |event| {
+                if let WalletEvent::PendingRequestChange(current) = event {
+                    pending.set(*current);
+                }
+            });
+            move || drop(subscription)
+        });
+    }
+    *pending
+}
+
+fn connect_label(ready_state: WalletReadyState, wallet: &str) -> String {
+    match ready_state {
+        WalletReadyState::Installed => format!("Connect {} Wallet", wallet),
+        WalletReadyState::Loadable => format!("Open in {} Wallet", wallet),
+        WalletReadyState::NotDetected | WalletReadyState::Unsupported => {
+            format!("Install {} Wallet", wallet)
+        }
+    }
+}
+
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props {
     pub phantom: Option<UseStateHandle<BaseWalletAdapter>>,
@@ -25,16 +76,23 @@ pub fn LoginForm(props: &Props) -> Html {
     let mut solflare_wallet_adapter = use_state(|| BaseWalletAdapter::default());
     let mut backpack_wallet_adapter = use_state(|| BaseWalletAdapter::default());
 
-    if phantom_wallet.is_some() {
-        phantom_wallet_adapter = phantom_wallet.clone().unwrap();
+    if let Some(phantom_wallet) = phantom_wallet {
+        phantom_wallet_adapter = phantom_wallet.clone();
     }
-    if solflare_wallet.is_some() {
-        solflare_wallet_adapter = solflare_wallet.clone().unwrap();
+    if let Some(solflare_wallet) = solflare_wallet {
+        solflare_wallet_adapter = solflare_wallet.clone();
     }
-    if backpack_wallet.is_some() {
-        backpack_wallet_adapter = backpack_wallet.clone().unwrap();
+    if let Some(backpack_wallet) = backpack_wallet {
+        backpack_wallet_adapter = backpack_wallet.clone();
     }
 
+    let phantom_ready_state = use_ready_state(&phantom_wallet_adapter);
+    let solflare_ready_state = use_ready_state(&solflare_wallet_adapter);
+    let backpack_ready_state = use_ready_state(&backpack_wallet_adapter);
+    let phantom_pending = use_pending_request(&phantom_wallet_adapter);
+    let solflare_pending = use_pending_request(&solflare_wallet_adapter);
+    let backpack_pending = use_pending_request(&backpack_wallet_adapter);
+
     let phantom_wallet_info = (*phantom_wallet_adapter).clone();
     let solflare_wallet_info = (*solflare_wallet_adapter).clone();
     let backpack_wallet_info = (*backpack_wallet_adapter).clone();
@@ -54,15 +112,14 @@ pub fn LoginForm(props: &Props) -> Html {
             spawn_local(async move {
                 let mut phantom_wallet_info = (*phantom_wallet_adapter).clone();
 
-                phantom_wallet_info
-                    .emitter
-                    .on("connect", move |public_key: Pubkey| {
+                let _subscription = phantom_wallet_info.subscribe(|event| {
+                    if let WalletEvent::Connect(public_key) = event {
                         log::info!("Event Listener: Got pubkey {}", public_key);
-                    });
+                    }
+                });
 
                 match phantom_wallet_info.connect().await {
                     Ok(conn) => {
-                        phantom_wallet_adapter.set(phantom_wallet_info);
                         connected.set(conn);
                     }
                     Err(err) => {
@@ -86,15 +143,14 @@ pub fn LoginForm(props: &Props) -> Html {
             spawn_local(async move {
                 let mut solflare_wallet_info = (*solflare_wallet_adapter).clone();
 
-                solflare_wallet_info
-                    .emitter
-                    .on("connect", move |public_key: Pubkey| {
+                let _subscription = solflare_wallet_info.subscribe(|event| {
+                    if let WalletEvent::Connect(public_key) = event {
                         log::info!("Event Listener: Got pubkey {}", public_key);
-                    });
+                    }
+                });
 
                 match solflare_wallet_info.connect().await {
                     Ok(conn) => {
-                        solflare_wallet_adapter.set(solflare_wallet_info);
                         connected.set(conn);
                     }
                     Err(err) => {
@@ -120,15 +176,14 @@ pub fn LoginForm(props: &Props) -> Html {
             spawn_local(async move {
                 let mut backpack_wallet_info = (*backpack_wallet_adapter).clone();
 
-                backpack_wallet_info
-                    .emitter
-                    .on("connect", move |public_key: Pubkey| {
+                let _subscription = backpack_wallet_info.subscribe(|event| {
+                    if let WalletEvent::Connect(public_key) = event {
                         log::info!("Event Listener: Got pubkey {}", public_key);
-                    });
+                    }
+                });
 
                 match backpack_wallet_info.connect().await {
                     Ok(conn) => {
-                        backpack_wallet_adapter.set(backpack_wallet_info.clone());
                         connected.set(conn);
                     }
                     Err(err) => {
@@ -158,21 +213,18 @@ pub fn LoginForm(props: &Props) -> Html {
 
                 match phantom_wallet_info.disconnect().await {
                     Ok(_) => {
-                        phantom_wallet_adapter.set(phantom_wallet_info);
                         connected.set(false);
                     }
                     Err(_err) => {}
                 }
                 match solflare_wallet_info.disconnect().await {
                     Ok(_) => {
-                        solflare_wallet_adapter.set(solflare_wallet_info);
                         connected.set(false);
                     }
                     Err(_err) => {}
                 }
                 match backpack_wallet_info.disconnect().await {
                     Ok(_) => {
-                        backpack_wallet_adapter.set(backpack_wallet_info);
                         connected.set(false);
                     }
                     Err(_err) => {}
@@ -188,34 +240,37 @@ pub fn LoginForm(props: &Props) -> Html {
                     if phantom_wallet.is_some() {
                         <button
                             onclick={connect_phantom_wallet.clone()}
+                            disabled={phantom_pending}
                         >
                             <img
                                 src={phantom_wallet_info.icon()}
                                 alt="Phantom Wallet"
                             />
-                            { "Connect Phantom Wallet" }
+                            { connect_label(phantom_ready_state, "Phantom") }
                         </button>
                     }
                     if solflare_wallet.is_some() {
                         <button
                             onclick={connect_solflare_wallet.clone()}
+                            disabled={solflare_pending}
                         >
                             <img
                                 src={solflare_wallet_info.icon()}
                                 alt="Solflare Wallet"
                             />
-                            { "Connect Solflare Wallet" }
+                            { connect_label(solflare_ready_state, "Solflare") }
                         </button>
                     }
                     if backpack_wallet.is_some() {
                         <button
                             onclick={connect_backpack_wallet.clone()}
+                            disabled={backpack_pending}
                         >
                             <img
                                 src={backpack_wallet_info.icon()}
                                 alt="Backpack Wallet"
                             />
-                            { "Connect Backpack Wallet" }
+                            { connect_label(backpack_ready_state, "Backpack") }
                         </button>
                     }
                 } else if let Some(ref _key) = phantom_wallet_info.public_key() {
@@ -1,11 +1,15 @@
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "web")]
 pub(crate) mod adapter;
 pub mod core;
+#[cfg(feature = "web")]
 pub mod forms;
+#[cfg(feature = "web")]
 pub mod provider;
 
 // re-export
 pub use solana_sdk::*;
+#[cfg(feature = "web")]
 pub use wasm_bindgen_futures::spawn_local;
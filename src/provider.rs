@@ -2,5 +2,7 @@
 pub mod dioxus;
 #[cfg(feature = "lep")]
 pub mod leptos;
+#[cfg(any(feature = "dio", feature = "lep", feature = "yew"))]
+pub(crate) mod remember;
 #[cfg(feature = "yew")]
 pub mod yew;
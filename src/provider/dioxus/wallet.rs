@@ -3,7 +3,10 @@ use crate::{
         traits::WalletAdapter,
         wallet::{BaseWalletAdapter, Wallet},
     },
-    provider::dioxus::local_storage::use_local_storage,
+    provider::{
+        dioxus::connection::ConnectionContext,
+        remember::{auto_connect, remember_wallets},
+    },
 };
 use dioxus::prelude::*;
 
@@ -13,33 +16,45 @@ pub struct WalletProviderProps {
     pub wallets: Vec<BaseWalletAdapter>,
     #[props(default = "walletName")]
     pub local_storage_key: &'static str,
+    /// Silently reconnects the wallet the user last connected, saved under
+    /// `local_storage_key`.
     #[props(default = false)]
     pub auto_connect: bool,
 }
 
 #[component]
 pub fn WalletProvider(props: WalletProviderProps) -> Element {
-    let (_wallet_name, _set_wallet_name) = use_local_storage(
-        props.local_storage_key.to_string(),
-        format!("{:?}", Wallet::default()).to_string(),
-    );
-
-    let wallet_context = use_memo(move || props.wallets.clone());
+    let local_storage_key = props.local_storage_key;
+    let wallet_context = use_memo(move || remember_wallets(&props.wallets, local_storage_key));
 
     use_context_provider(|| (*wallet_context)().clone());
 
+    // Lets the wallets fill in recent blockhashes through the surrounding `ConnectionProvider`.
+    use_hook(move || {
+        if let Some(connection) = try_consume_context::<ConnectionContext>() {
+            for wallet in wallet_context() {
+                wallet.set_connection(connection.connection.clone());
+            }
+        }
+    });
+
+    let enabled = props.auto_connect;
+    use_hook(move || {
+        if enabled {
+            spawn(auto_connect(wallet_context(), local_storage_key));
+        }
+    });
+
     rsx! { { &props.children } }
 }
 
 pub fn use_wallet(wallet_name: Wallet) -> BaseWalletAdapter {
     let wallets = use_context::<Vec<BaseWalletAdapter>>();
-    let (_wallet_name, _set_wallet_name) = use_local_storage(
-        "walletName".to_string(),
-        format!("{:?}", wallet_name).to_string(),
-    );
+    let adapter: BaseWalletAdapter = wallet_name.into();
+    let name = adapter.name();
     wallets
         .iter()
-        .find(|wallet| wallet.name() == format!("{:?}", wallet_name).to_string())
+        .find(|wallet| wallet.name() == name)
         .cloned()
-        .expect("Wallet not found")
+        .unwrap_or(adapter)
 }
@@ -1,5 +1,5 @@
+use crate::core::client::WasmClient as RpcClient;
 use leptos::*;
-use solana_client_wasm::WasmClient as RpcClient;
 use solana_sdk::commitment_config::CommitmentConfig;
 use std::{fmt, ops::Deref, rc::Rc, sync::Arc};
 
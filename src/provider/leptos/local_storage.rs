@@ -2,8 +2,10 @@ use gloo_storage::{LocalStorage, Storage};
 use leptos::*;
 
 pub fn use_local_storage(key: String, initial_value: String) -> (String, Callback<String>) {
-    LocalStorage::set(&key, &initial_value).ok();
-    let stored_value: String = LocalStorage::get(key.clone()).unwrap_or(initial_value.clone());
+    let stored_value: String = LocalStorage::get(key.clone()).unwrap_or_else(|_| {
+        LocalStorage::set(&key, &initial_value).ok();
+        initial_value.clone()
+    });
 
     let (stored_value, set_stored_value) = create_signal(stored_value);
 
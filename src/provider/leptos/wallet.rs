@@ -1,11 +1,12 @@
 use crate::{
-    core::{
-        traits::WalletAdapter,
-        wallet::{BaseWalletAdapter, Wallet},
+    core::{traits::WalletAdapter, wallet::BaseWalletAdapter},
+    provider::{
+        leptos::connection::ConnectionContext,
+        remember::{self, remember_wallets},
     },
-    provider::leptos::local_storage::use_local_storage,
 };
 use leptos::*;
+use wasm_bindgen_futures::spawn_local;
 
 #[derive(Clone)]
 pub struct Wallets {
@@ -17,13 +18,26 @@ pub fn WalletProvider(
     children: Children,
     wallets: Vec<BaseWalletAdapter>,
     #[prop(default = "walletName")] local_storage_key: &'static str,
+    /// Silently reconnects the wallet the user last connected, saved under
+    /// `local_storage_key`.
+    #[prop(default = false)]
+    auto_connect: bool,
 ) -> impl IntoView {
-    let (_wallet_name, _set_wallet_name) = use_local_storage(
-        local_storage_key.to_string(),
-        format!("{:?}", Wallet::default()).to_string(),
-    );
+    let wallet_context = create_memo(move |_| remember_wallets(&wallets, local_storage_key));
 
-    let wallet_context = create_memo(move |_| wallets.clone());
+    // Lets the wallets fill in recent blockhashes through the surrounding `ConnectionProvider`.
+    if let Some(connection) = use_context::<ConnectionContext>() {
+        for wallet in wallet_context.get_untracked() {
+            wallet.set_connection(connection.connection.clone());
+        }
+    }
+
+    if auto_connect {
+        spawn_local(remember::auto_connect(
+            wallet_context.get_untracked(),
+            local_storage_key,
+        ));
+    }
 
     view! {
         <Provider<Wallets> value={Wallets { wallets: wallet_context.get_untracked()}}>
@@ -36,14 +50,12 @@ where
     W: Into<BaseWalletAdapter> + std::fmt::Debug,
 {
     let wallets = use_context::<Wallets>().expect("No WalletContext found");
-    let (_wallet_name, _set_wallet_name) = use_local_storage(
-        "walletName".to_string(),
-        format!("{:?}", Wallet::default()).to_string(),
-    );
+    let adapter: BaseWalletAdapter = wallet_name.into();
+    let name = adapter.name();
     wallets
         .wallets
         .iter()
-        .find(|wallet| wallet.name() == format!("{:?}", wallet_name).to_string())
+        .find(|wallet| wallet.name() == name)
         .cloned()
-        .expect("Wallet not found")
+        .unwrap_or(adapter)
 }
@@ -0,0 +1,53 @@
+//! Remembers the wallet the user connected, so the providers can reconnect it silently on the
+//! next visit.
+
+use gloo_storage::{LocalStorage, Storage};
+
+use crate::core::{events::WalletEvent, traits::WalletAdapter, wallet::BaseWalletAdapter};
+
+/// Saves a wallet's name under `key` when it connects, and forgets it when it disconnects.
+pub(crate) fn remember_wallets(
+    wallets: &[BaseWalletAdapter],
+    key: &'static str,
+) -> Vec<BaseWalletAdapter> {
+    wallets
+        .iter()
+        .cloned()
+        .inspect(|wallet| {
+            let name = wallet.name();
+            // Kept for as long as the wallet, without polling its ready state meanwhile.
+            wallet
+                .subscribe_unwatched(move |event| match event {
+                    WalletEvent::Connect(_) => {
+                        if let Err(err) = LocalStorage::set(key, &name) {
+                            log::error!("Failed to set {} in local storage: {}", key, err);
+                        }
+                    }
+                    WalletEvent::Disconnect
+                        if LocalStorage::get::<String>(key).is_ok_and(|stored| stored == name) =>
+                    {
+                        LocalStorage::delete(key);
+                    }
+                    _ => {}
+                })
+                .forget();
+        })
+        .collect()
+}
+
+/// Silently reconnects the wallet saved under `key`, if it is one of `wallets`.
+pub(crate) async fn auto_connect(wallets: Vec<BaseWalletAdapter>, key: &'static str) {
+    let Ok(name) = LocalStorage::get::<String>(key) else {
+        return;
+    };
+    let Some(mut wallet) = wallets.into_iter().find(|wallet| wallet.name() == name) else {
+        log::info!("No wallet named {} to reconnect", name);
+        return;
+    };
+
+    match wallet.auto_connect().await {
+        Ok(true) => log::info!("Reconnected to {}", name),
+        Ok(false) => log::info!("{} did not reconnect", name),
+        Err(err) => log::error!("Failed to reconnect to {}: {}", name, err),
+    }
+}
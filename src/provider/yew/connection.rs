@@ -2,7 +2,7 @@ use std::{fmt, ops::Deref, rc::Rc, sync::Arc};
 
 use yew::prelude::*;
 
-use solana_client_wasm::WasmClient as RpcClient;
+use crate::core::client::WasmClient as RpcClient;
 
 use solana_sdk::commitment_config::CommitmentConfig;
 
@@ -3,8 +3,10 @@ use yew::prelude::*;
 
 #[hook]
 pub fn use_local_storage(key: String, initial_value: String) -> (String, Callback<String>) {
-    LocalStorage::set(&key, &initial_value).ok();
-    let stored_value: String = LocalStorage::get(key.clone()).unwrap_or(initial_value.clone());
+    let stored_value: String = LocalStorage::get(key.clone()).unwrap_or_else(|_| {
+        LocalStorage::set(&key, &initial_value).ok();
+        initial_value.clone()
+    });
 
     let stored_value = use_state(|| stored_value);
     let data = (*stored_value).clone();
@@ -1,11 +1,12 @@
 use crate::{
-    core::{
-        traits::WalletAdapter,
-        wallet::{BaseWalletAdapter, Wallet},
+    core::{traits::WalletAdapter, wallet::BaseWalletAdapter},
+    provider::{
+        remember::{auto_connect, remember_wallets},
+        yew::connection::ConnectionContext,
     },
-    provider::yew::local_storage::use_local_storage,
 };
 
+use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 
 #[derive(Properties, Clone, PartialEq)]
@@ -14,21 +15,42 @@ pub struct WalletProviderProps {
     pub wallets: Vec<BaseWalletAdapter>,
     #[prop_or("walletName")]
     pub local_storage_key: &'static str,
+    /// Silently reconnects the wallet the user last connected, saved under
+    /// `local_storage_key`.
     #[prop_or(false)]
     pub auto_connect: bool,
 }
 
 #[function_component]
 pub fn WalletProvider(props: &WalletProviderProps) -> Html {
-    let (wallet_name, _set_wallet_name) = use_local_storage(
-        props.local_storage_key.to_string(),
-        format!("{:?}", Wallet::default()).to_string(),
-    );
-
-    let wallet_context = use_memo((props.wallets.clone(), wallet_name.clone()), |_| {
-        props.wallets.clone()
+    let local_storage_key = props.local_storage_key;
+    let wallet_context = use_memo(props.wallets.clone(), |wallets| {
+        remember_wallets(wallets, local_storage_key)
     });
 
+    // Lets the wallets fill in recent blockhashes through the surrounding `ConnectionProvider`.
+    let connection = use_context::<ConnectionContext>();
+    {
+        let wallets = (*wallet_context).clone();
+        use_effect_with(connection, move |connection| {
+            if let Some(connection) = connection {
+                for wallet in &wallets {
+                    wallet.set_connection(connection.connection.clone());
+                }
+            }
+        });
+    }
+
+    {
+        let wallets = (*wallet_context).clone();
+        let enabled = props.auto_connect;
+        use_effect_with((), move |_| {
+            if enabled {
+                spawn_local(auto_connect(wallets, local_storage_key));
+            }
+        });
+    }
+
     html! {
         <ContextProvider<Vec<BaseWalletAdapter>> context={(*wallet_context).clone()}>
             { props.children.clone() }
@@ -42,13 +64,11 @@ where
     W: Into<BaseWalletAdapter> + std::fmt::Debug,
 {
     let wallets = use_context::<Vec<BaseWalletAdapter>>().expect("No WalletContext found");
-    let (_wallet_name, _set_wallet_name) = use_local_storage(
-        "walletName".to_string(),
-        format!("{:?}", wallet_name).to_string(),
-    );
+    let adapter: BaseWalletAdapter = wallet_name.into();
+    let name = adapter.name();
     wallets
         .iter()
-        .find(|wallet| wallet.name() == format!("{:?}", wallet_name).to_string())
+        .find(|wallet| wallet.name() == name)
         .cloned()
-        .expect("Wallet not found")
+        .unwrap_or(adapter)
 }
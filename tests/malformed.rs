@@ -0,0 +1,283 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::sync::Arc;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+use wasi_sol::{
+    core::{
+        client::{Cluster, WasmClient},
+        error::{WalletError, WalletOperation},
+        response::SignInInput,
+        traits::WalletAdapter,
+        transaction::SendMode,
+        wallet::{BaseWalletAdapter, Wallet},
+    },
+    hash::Hash,
+    pubkey::Pubkey,
+    signer::{keypair::keypair_from_seed, Signer},
+    transaction::Transaction,
+};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen(inline_js = r#"
+const cyclic = {};
+cyclic.self = cyclic;
+cyclic.signature = cyclic;
+
+const responses = [
+    undefined,
+    null,
+    0,
+    NaN,
+    10n,
+    true,
+    "",
+    "not base58 !",
+    [],
+    [null, 1, "x"],
+    {},
+    Object.create(null),
+    cyclic,
+    { signature: "0OIl" },
+    { signature: 42 },
+    { signature: [1, 2, 3] },
+    { signature: { data: "x" } },
+    { signature: { 0: 1, 5: 2 } },
+    { signature: new Uint8Array(64), publicKey: "x" },
+    { signatures: null },
+    { signatures: [null, {}, 1, "x", { publicKey: 1, signature: 1 }] },
+    { signatures: [new Uint8Array(64)] },
+    new Uint8Array(3),
+    new Uint8Array(64),
+    { serialize() { throw new Error("boom"); } },
+    { serialize() { return 1; } },
+    { account: { address: {} }, signature: new Uint8Array(1), signedMessage: null },
+    { address: Object.create(null), signature: new Uint8Array(64), signedMessage: new Uint8Array(0) },
+    { toString() { throw new Error("boom"); } },
+];
+
+let state = { address: undefined, response: undefined, mode: "connect" };
+
+function reply() {
+    switch (state.mode) {
+        case "connect":
+            return Promise.resolve({ accounts: [account], publicKey: state.address });
+        case "throw":
+            throw state.response;
+        case "reject":
+            return Promise.reject(state.response);
+        default:
+            return Promise.resolve(state.response);
+    }
+}
+
+const provider = {
+    get publicKey() {
+        if (state.mode === "connect") return state.address;
+        if (state.mode === "throw") throw state.response;
+        return state.response;
+    },
+};
+for (const method of [
+    "connect",
+    "disconnect",
+    "request",
+    "signIn",
+    "signMessage",
+    "signTransaction",
+    "signAllTransactions",
+    "signAndSendTransaction",
+    "signAndSendAllTransactions",
+]) {
+    provider[method] = reply;
+}
+
+window.solana = provider;
+window.solflare = provider;
+window.xnft = { solana: provider };
+
+const account = {
+    get address() {
+        return state.address;
+    },
+    publicKey: new Uint8Array(32),
+    chains: ["solana:devnet"],
+    features: ["solana:signTransaction", "solana:signMessage"],
+};
+const feature = (method) => ({ version: "1.0.0", [method]: reply });
+const wallet = {
+    version: "1.0.0",
+    name: "Malformed Stub",
+    icon: "data:image/svg+xml;base64,PHN2Zy8+",
+    chains: ["solana:devnet"],
+    features: {
+        "standard:connect": feature("connect"),
+        "standard:disconnect": feature("disconnect"),
+        "standard:events": { version: "1.0.0", on: () => () => {} },
+        "solana:signTransaction": feature("signTransaction"),
+        "solana:signAndSendTransaction": feature("signAndSendTransaction"),
+        "solana:signMessage": feature("signMessage"),
+        "solana:signIn": feature("signIn"),
+    },
+    // Wallets may list junk next to the connected account.
+    accounts: [null, 1, "x", {}, { chains: null }, account],
+};
+
+export function registerMalformedWallet() {
+    const callback = ({ register }) => register(wallet);
+    try {
+        window.dispatchEvent(
+            new CustomEvent("wallet-standard:register-wallet", { detail: callback })
+        );
+    } catch (error) {
+        console.error(error);
+    }
+    window.addEventListener("wallet-standard:app-ready", ({ detail: api }) => callback(api));
+}
+
+export function responseCount() {
+    return responses.length;
+}
+
+export function setAddress(address) {
+    state.address = address;
+}
+
+export function respondWith(index, mode) {
+    state.response = responses[index];
+    state.mode = mode;
+}
+
+export function rejectWith(code, message) {
+    state.response = { code, message };
+    state.mode = "reject";
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = registerMalformedWallet)]
+    fn register_malformed_wallet();
+
+    #[wasm_bindgen(js_name = responseCount)]
+    fn response_count() -> usize;
+
+    #[wasm_bindgen(js_name = setAddress)]
+    fn set_address(address: &str);
+
+    #[wasm_bindgen(js_name = respondWith)]
+    fn respond_with(index: usize, mode: &str);
+
+    #[wasm_bindgen(js_name = rejectWith)]
+    fn reject_with(code: i32, message: &str);
+}
+
+fn wallets() -> Vec<Wallet> {
+    register_malformed_wallet();
+    vec![
+        Wallet::Phantom,
+        Wallet::Solflare,
+        Wallet::Backpack,
+        Wallet::Standard("Malformed Stub".to_string()),
+    ]
+}
+
+// A real ed25519 key: `Pubkey::new_unique` keys can be small order points, for which an
+// all-zero signature verifies.
+fn public_key() -> Pubkey {
+    keypair_from_seed(&[7; 32]).unwrap().pubkey()
+}
+
+async fn connected(wallet: &Wallet, public_key: &Pubkey) -> BaseWalletAdapter {
+    set_address(&public_key.to_string());
+    respond_with(0, "connect");
+
+    let mut adapter: BaseWalletAdapter = wallet.clone().into();
+    adapter.set_cluster(Some(Cluster::Devnet));
+    adapter.connect().await.unwrap();
+    assert_eq!(adapter.public_key(), Some(*public_key), "{:?}", wallet);
+    adapter
+}
+
+#[wasm_bindgen_test]
+async fn malformed_provider_responses_are_errors() {
+    let public_key = public_key();
+    let client = Arc::new(WasmClient::new("http://127.0.0.1:1"));
+    let transaction = || {
+        let mut transaction = Transaction::new_with_payer(&[], Some(&public_key));
+        transaction.message.recent_blockhash = Hash::new_unique();
+        transaction
+    };
+
+    for wallet in wallets() {
+        for index in 0..response_count() {
+            for mode in ["resolve", "reject", "throw"] {
+                let case = format!("{:?}, response {} ({})", wallet, index, mode);
+
+                set_address(&public_key.to_string());
+                respond_with(index, mode);
+                let mut adapter: BaseWalletAdapter = wallet.clone().into();
+                let _ = adapter.connect().await;
+
+                let mut adapter = connected(&wallet, &public_key).await;
+                respond_with(index, mode);
+
+                assert!(
+                    adapter.sign_transaction(transaction()).await.is_err(),
+                    "{}",
+                    case
+                );
+                if let Ok(results) = adapter.sign_all_transactions([transaction()]).await {
+                    assert!(results.iter().all(Result::is_err), "{}", case);
+                }
+                assert!(
+                    adapter.sign_send_transaction(transaction()).await.is_err(),
+                    "{}",
+                    case
+                );
+                adapter.set_connection(client.clone());
+                if let Ok(results) = adapter
+                    .sign_send_all_transactions([transaction()], SendMode::default())
+                    .await
+                {
+                    assert!(results.iter().all(Result::is_err), "{}", case);
+                }
+                assert!(adapter.sign_message("hello").await.is_err(), "{}", case);
+                assert!(
+                    adapter.sign_in(SignInInput::default()).await.is_err(),
+                    "{}",
+                    case
+                );
+                let _ = adapter.disconnect().await;
+            }
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn keeps_provider_error_codes() {
+    let public_key = public_key();
+
+    for wallet in wallets() {
+        let mut adapter = connected(&wallet, &public_key).await;
+        reject_with(4001, "User rejected the request.");
+
+        let err = adapter.sign_message("hello").await.unwrap_err();
+        assert!(err.is_user_rejection(), "{:?}: {:?}", wallet, err);
+        assert_eq!(err.operation(), Some(WalletOperation::SignMessage));
+        assert!(matches!(
+            err,
+            WalletError::WalletProviderError { ref message, .. }
+                if message == "User rejected the request."
+        ));
+
+        reject_with(-32603, "Internal error");
+        let err = adapter
+            .sign_transaction(Transaction::new_with_payer(&[], Some(&public_key)))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Some(WalletError::INTERNAL_ERROR));
+        assert!(!err.is_user_rejection());
+    }
+}
@@ -0,0 +1,120 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::time::Duration;
+
+use futures::join;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+
+use wasi_sol::{
+    core::{
+        error::WalletError,
+        timeout::WalletTimeouts,
+        traits::WalletAdapter,
+        wallet::{BaseWalletAdapter, Wallet},
+    },
+    pubkey::Pubkey,
+};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+// A Wallet Standard wallet whose users never answer its prompts.
+#[wasm_bindgen(inline_js = r#"
+export function registerSilentWallet(name, address) {
+    const account = {
+        address,
+        publicKey: new Uint8Array(32),
+        chains: ["solana:devnet"],
+        features: ["solana:signTransaction", "solana:signMessage"],
+    };
+    const wallet = {
+        version: "1.0.0",
+        name,
+        icon: "data:image/svg+xml;base64,PHN2Zy8+",
+        chains: ["solana:devnet"],
+        features: {
+            "standard:connect": {
+                version: "1.0.0",
+                connect: async () => {
+                    wallet.accounts = [account];
+                    return { accounts: wallet.accounts };
+                },
+            },
+            "standard:events": { version: "1.0.0", on: () => () => {} },
+            "solana:signTransaction": {
+                version: "1.0.0",
+                supportedTransactionVersions: ["legacy", 0],
+                signTransaction: () => new Promise(() => {}),
+            },
+            "solana:signMessage": {
+                version: "1.0.0",
+                signMessage: () => new Promise(() => {}),
+            },
+        },
+        accounts: [],
+    };
+    const callback = ({ register }) => register(wallet);
+    window.dispatchEvent(new CustomEvent("wallet-standard:register-wallet", { detail: callback }));
+    window.addEventListener("wallet-standard:app-ready", ({ detail: api }) => callback(api));
+}
+
+export function delay(ms) {
+    return new Promise((resolve) => setTimeout(resolve, ms));
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = registerSilentWallet)]
+    fn register_silent_wallet(name: &str, address: &str);
+
+    fn delay(ms: u32) -> js_sys::Promise;
+}
+
+async fn connected_silent_wallet(name: &str) -> BaseWalletAdapter {
+    register_silent_wallet(name, &Pubkey::new_unique().to_string());
+    let mut adapter: BaseWalletAdapter = Wallet::Standard(name.to_string()).into();
+    assert!(adapter.connect().await.unwrap());
+    adapter
+}
+
+#[wasm_bindgen_test]
+async fn cancel_aborts_requests_queued_behind_a_pending_one() {
+    let adapter = connected_silent_wallet("Cancelled Stub").await;
+    let (mut first, mut queued) = (adapter.clone(), adapter.clone());
+    let cancel = adapter.cancel_handle();
+
+    let (first, queued, _) = join!(
+        first.sign_message("first"),
+        queued.sign_message("queued"),
+        async {
+            let _ = JsFuture::from(delay(50)).await;
+            cancel.cancel();
+        }
+    );
+
+    assert_eq!(first.unwrap_err(), WalletError::WalletCancelledError);
+    assert_eq!(queued.unwrap_err(), WalletError::WalletCancelledError);
+    assert!(!adapter.pending_request());
+}
+
+#[wasm_bindgen_test]
+async fn timeouts_cover_the_wait_for_earlier_requests() {
+    let adapter = connected_silent_wallet("Queued Stub").await;
+    let (mut first, mut queued) = (adapter.clone(), adapter.clone());
+    let cancel = adapter.cancel_handle();
+
+    // The first request started without a timeout, so only the queued one can time out.
+    let (first, queued) = join!(first.sign_message("first"), async {
+        let _ = JsFuture::from(delay(10)).await;
+        adapter.set_timeouts(WalletTimeouts {
+            sign: Some(Duration::from_millis(100)),
+            ..Default::default()
+        });
+        let queued = queued.sign_message("queued").await;
+        cancel.cancel();
+        queued
+    });
+
+    assert_eq!(queued.unwrap_err(), WalletError::WalletTimeoutError);
+    assert_eq!(first.unwrap_err(), WalletError::WalletCancelledError);
+}
@@ -0,0 +1,225 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc, sync::Arc, time::Duration};
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+use wasi_sol::{
+    commitment_config::CommitmentLevel,
+    core::{
+        client::WasmClient,
+        error::WalletError,
+        events::{Subscription, WalletEvent},
+        rebroadcast::{RebroadcastConfig, SendStrategy},
+        traits::{WalletAdapter, WalletAdapterEvents},
+        wallet::{BaseWalletAdapter, Wallet},
+    },
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::Signature,
+    system_instruction,
+    transaction::Transaction,
+};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+// A wallet that only signs, and a JSON-RPC node that drops the first `drops` transactions it
+// is sent and then lands the next one.
+#[wasm_bindgen(inline_js = r#"
+const node = {};
+
+export function resetStubs(publicKey, signature, blockhash, drops, expireAfter) {
+    Object.assign(node, {
+        signature,
+        blockhash,
+        drops,
+        expireAfter,
+        sends: [],
+        walletMethods: [],
+        landed: false,
+        heightChecks: 0,
+    });
+    window.solana = {
+        connect: async () => ({ publicKey }),
+        on() {},
+        removeListener() {},
+        request: async ({ method }) => {
+            node.walletMethods.push(method);
+            return { signature, publicKey };
+        },
+    };
+}
+
+function reply(method, params) {
+    switch (method) {
+        case "getLatestBlockhash":
+            return { context: { slot: 1 }, value: { blockhash: node.blockhash, lastValidBlockHeight: 1234 } };
+        case "sendTransaction":
+            node.sends.push(params[1]);
+            node.landed = node.landed || node.sends.length > node.drops;
+            return node.signature;
+        case "getSignatureStatuses":
+            return {
+                context: { slot: 10 },
+                value: [node.landed
+                    ? { slot: 9, confirmations: 1, err: null, confirmationStatus: "confirmed" }
+                    : null],
+            };
+        case "getBlockHeight":
+            node.heightChecks += 1;
+            return node.heightChecks > node.expireAfter ? 2000 : 1000;
+    }
+    return null;
+}
+
+globalThis.fetch = async (input, init) => {
+    const { id, method, params } = JSON.parse(init ? init.body : await input.text());
+    const response = new Response(JSON.stringify({ jsonrpc: "2.0", id, result: reply(method, params) }), {
+        headers: { "content-type": "application/json" },
+    });
+    Object.defineProperty(response, "url", { value: "http://localhost:8899/" });
+    return response;
+};
+
+export function sendConfigs() { return JSON.stringify(node.sends); }
+export function walletMethods() { return node.walletMethods; }
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = resetStubs)]
+    fn reset_stubs(
+        public_key: &str,
+        signature: &str,
+        blockhash: &str,
+        drops: u32,
+        expire_after: u32,
+    );
+    #[wasm_bindgen(js_name = sendConfigs)]
+    fn send_configs() -> String;
+    #[wasm_bindgen(js_name = walletMethods)]
+    fn wallet_methods() -> Vec<String>;
+}
+
+const STUB_SIGNATURE: [u8; 64] = [7; 64];
+
+async fn connected_adapter(drops: u32, expire_after: u32) -> (BaseWalletAdapter, Transaction) {
+    let public_key = Pubkey::new_unique();
+    let signature = Signature::from(STUB_SIGNATURE);
+    reset_stubs(
+        &public_key.to_string(),
+        &signature.to_string(),
+        &Hash::new_unique().to_string(),
+        drops,
+        expire_after,
+    );
+
+    let mut adapter: BaseWalletAdapter = Wallet::Phantom.into();
+    assert!(adapter.connect().await.unwrap());
+    // The stub's signature is not a valid ed25519 signature for the account.
+    adapter.set_skip_signature_verification(true);
+    adapter.set_connection(Arc::new(WasmClient::new("http://localhost:8899")));
+
+    let transfer = system_instruction::transfer(&public_key, &Pubkey::new_unique(), 1);
+    let transaction = Transaction::new_with_payer(&[transfer], Some(&public_key));
+    (adapter, transaction)
+}
+
+fn sent_signatures(adapter: &BaseWalletAdapter) -> (Subscription, Rc<RefCell<Vec<Signature>>>) {
+    let sent = Rc::new(RefCell::new(Vec::new()));
+    let listener = sent.clone();
+    let subscription = adapter.subscribe(move |event| {
+        if let WalletEvent::TransactionSent(signature) = event {
+            listener.borrow_mut().push(*signature);
+        }
+    });
+    (subscription, sent)
+}
+
+fn send_configs_json() -> Vec<serde_json::Value> {
+    serde_json::from_str(&send_configs()).unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn resends_dropped_transactions_until_they_land() {
+    let (mut adapter, transaction) = connected_adapter(2, u32::MAX).await;
+
+    let report = adapter
+        .sign_rebroadcast_transaction(
+            transaction,
+            RebroadcastConfig {
+                interval: Duration::from_millis(50),
+                preflight_commitment: Some(CommitmentLevel::Processed),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let sends = send_configs_json();
+    assert!(report.resends >= 2);
+    assert_eq!(report.resends, sends.len() - 1);
+    assert_eq!(report.signature, Signature::from(STUB_SIGNATURE));
+    assert_eq!(report.confirmation.slot, 9);
+    assert_eq!(wallet_methods(), vec!["signTransaction"]);
+
+    assert_eq!(sends[0]["skipPreflight"], false);
+    assert_eq!(sends[0]["preflightCommitment"], "processed");
+    assert_eq!(sends[0]["maxRetries"], 0);
+    assert!(sends[1..]
+        .iter()
+        .all(|config| config["skipPreflight"] == true));
+}
+
+#[wasm_bindgen_test]
+async fn stops_resending_after_max_retries_and_reports_expiry() {
+    let (mut adapter, transaction) = connected_adapter(u32::MAX, 3).await;
+
+    let result = adapter
+        .sign_rebroadcast_transaction(
+            transaction,
+            RebroadcastConfig {
+                interval: Duration::from_millis(50),
+                max_retries: Some(1),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    assert_eq!(
+        result,
+        Err(WalletError::WalletTransactionExpiredError {
+            signature: Signature::from(STUB_SIGNATURE)
+        })
+    );
+    assert_eq!(send_configs_json().len(), 2);
+}
+
+#[wasm_bindgen_test]
+async fn sign_send_transaction_follows_the_send_strategy() {
+    let (mut adapter, transaction) = connected_adapter(1, u32::MAX).await;
+    adapter.set_send_strategy(SendStrategy::Rebroadcast(RebroadcastConfig {
+        interval: Duration::from_millis(50),
+        ..Default::default()
+    }));
+
+    let (_subscription, sent) = sent_signatures(&adapter);
+
+    let signature = adapter.sign_send_transaction(transaction).await.unwrap();
+
+    assert_eq!(signature, Signature::from(STUB_SIGNATURE));
+    assert_eq!(wallet_methods(), vec!["signTransaction"]);
+    assert!(send_configs_json().len() >= 2);
+    // Resends are not new transactions.
+    assert_eq!(*sent.borrow(), vec![signature]);
+}
+
+#[wasm_bindgen_test]
+async fn sign_send_transaction_through_the_wallet_emits_transaction_sent() {
+    let (mut adapter, transaction) = connected_adapter(0, u32::MAX).await;
+    let (_subscription, sent) = sent_signatures(&adapter);
+
+    let signature = adapter.sign_send_transaction(transaction).await.unwrap();
+
+    assert_eq!(wallet_methods(), vec!["signAndSendTransaction"]);
+    assert_eq!(*sent.borrow(), vec![signature]);
+}
@@ -0,0 +1,331 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+use wasi_sol::{
+    commitment_config::CommitmentLevel,
+    core::{
+        adapters::StandardSignerWalletAdapter,
+        client::{Cluster, WasmClient},
+        error::WalletError,
+        traits::{SignerWalletAdapter, WalletAdapter},
+        transaction::{SendMode, SendOptions},
+        wallet::{BaseWalletAdapter, Wallet},
+    },
+    hash::Hash,
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::Signature,
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+// A Wallet Standard wallet that only signs, filling the first signature with 7s, and a JSON-RPC
+// node on which every transaction lands, failing if `failing` is set.
+#[wasm_bindgen(inline_js = r#"
+const node = { sends: [], prompts: 0 };
+
+export function registerSigner(name, address, failing) {
+    node.sends = [];
+    node.prompts = 0;
+    node.failing = failing;
+
+    const account = {
+        address,
+        publicKey: new Uint8Array(32),
+        chains: ["solana:devnet"],
+        features: ["solana:signTransaction"],
+    };
+    const wallet = {
+        version: "1.0.0",
+        name,
+        icon: "data:image/svg+xml;base64,PHN2Zy8+",
+        chains: ["solana:devnet"],
+        features: {
+            "standard:connect": {
+                version: "1.0.0",
+                connect: async () => {
+                    wallet.accounts = [account];
+                    return { accounts: wallet.accounts };
+                },
+            },
+            "standard:events": { version: "1.0.0", on: () => () => {} },
+            "solana:signTransaction": {
+                version: "1.0.0",
+                supportedTransactionVersions: ["legacy", 0],
+                signTransaction: async (...inputs) => {
+                    node.prompts += 1;
+                    return inputs.map(({ transaction }) => {
+                        const signedTransaction = transaction.slice();
+                        signedTransaction.fill(7, 1, 65);
+                        return { signedTransaction };
+                    });
+                },
+            },
+        },
+        accounts: [],
+    };
+    const callback = ({ register }) => register(wallet);
+    window.dispatchEvent(new CustomEvent("wallet-standard:register-wallet", { detail: callback }));
+    window.addEventListener("wallet-standard:app-ready", ({ detail: api }) => callback(api));
+}
+
+function reply(method, params) {
+    switch (method) {
+        case "sendTransaction":
+            node.sends.push(params[1]);
+            return node.signature;
+        case "getSignatureStatuses":
+            return {
+                context: { slot: 10 },
+                value: [{
+                    slot: 9,
+                    confirmations: 1,
+                    err: node.failing ? { InstructionError: [0, { Custom: 1 }] } : null,
+                    confirmationStatus: "confirmed",
+                }],
+            };
+    }
+    return null;
+}
+
+globalThis.fetch = async (input, init) => {
+    const { id, method, params } = JSON.parse(init ? init.body : await input.text());
+    const response = new Response(JSON.stringify({ jsonrpc: "2.0", id, result: reply(method, params) }), {
+        headers: { "content-type": "application/json" },
+    });
+    Object.defineProperty(response, "url", { value: "http://localhost:8899/" });
+    return response;
+};
+
+export function setSignature(signature) {
+    node.signature = signature;
+}
+
+export function sendCount() {
+    return node.sends.length;
+}
+
+export function promptCount() {
+    return node.prompts;
+}
+
+export function lastSendConfig() {
+    return JSON.stringify(node.sends[node.sends.length - 1]);
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = registerSigner)]
+    fn register_signer(name: &str, address: &str, failing: bool);
+
+    #[wasm_bindgen(js_name = setSignature)]
+    fn set_signature(signature: &str);
+
+    #[wasm_bindgen(js_name = sendCount)]
+    fn send_count() -> usize;
+
+    #[wasm_bindgen(js_name = promptCount)]
+    fn prompt_count() -> usize;
+
+    #[wasm_bindgen(js_name = lastSendConfig)]
+    fn last_send_config() -> String;
+}
+
+const SIGNATURE: [u8; 64] = [7; 64];
+
+async fn connected_signer(
+    name: &str,
+    failing: bool,
+) -> (BaseWalletAdapter, Arc<WasmClient>, Transaction) {
+    let public_key = Pubkey::new_unique();
+    register_signer(name, &public_key.to_string(), failing);
+    set_signature(&Signature::from(SIGNATURE).to_string());
+
+    let mut adapter: BaseWalletAdapter = Wallet::Standard(name.to_string()).into();
+    assert!(adapter.connect().await.unwrap());
+    // The stub's signature is not a valid ed25519 signature for the account.
+    adapter.set_skip_signature_verification(true);
+    adapter.set_cluster(Some(Cluster::Devnet));
+    let client = Arc::new(WasmClient::new("http://localhost:8899"));
+    adapter.set_connection(client.clone());
+
+    let transfer = system_instruction::transfer(&public_key, &Pubkey::new_unique(), 1);
+    let mut transaction = Transaction::new_with_payer(&[transfer], Some(&public_key));
+    transaction.message.recent_blockhash = Hash::new_unique();
+    (adapter, client, transaction)
+}
+
+fn failed() -> WalletError {
+    WalletError::WalletTransactionFailedError {
+        signature: Signature::from(SIGNATURE),
+        error: TransactionError::InstructionError(0, InstructionError::Custom(1)),
+    }
+}
+
+#[wasm_bindgen_test]
+async fn sign_all_transactions_signs_the_batch_behind_one_prompt() {
+    let (mut adapter, _, transaction) = connected_signer("Batch Stub", false).await;
+    let transactions = vec![transaction; 3];
+
+    let results = adapter
+        .sign_all_transactions(transactions.clone())
+        .await
+        .unwrap();
+
+    assert_eq!(prompt_count(), 1);
+    assert_eq!(results.len(), 3);
+    for (result, unsigned) in results.into_iter().zip(transactions) {
+        let signed = result.unwrap();
+        assert_eq!(signed.signatures(), &[Signature::from(SIGNATURE)]);
+        assert_eq!(signed.message_data(), unsigned.message_data());
+    }
+    assert_eq!(send_count(), 0);
+}
+
+#[wasm_bindgen_test]
+async fn sequential_sends_stop_at_a_transaction_that_fails_on_chain() {
+    let (mut adapter, _, transaction) = connected_signer("Sequential Stub", true).await;
+
+    let results = adapter
+        .sign_send_all_transactions(vec![transaction; 3], SendMode::Sequential)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        results,
+        vec![
+            Err(failed()),
+            Err(WalletError::WalletSendTransactionError),
+            Err(WalletError::WalletSendTransactionError),
+        ]
+    );
+    assert_eq!(send_count(), 1);
+}
+
+#[wasm_bindgen_test]
+async fn parallel_sends_report_each_on_chain_failure() {
+    let (mut adapter, _, transaction) = connected_signer("Parallel Stub", true).await;
+
+    let results = adapter
+        .sign_send_all_transactions(vec![transaction; 2], SendMode::Parallel)
+        .await
+        .unwrap();
+
+    assert_eq!(results, vec![Err(failed()), Err(failed())]);
+    assert_eq!(send_count(), 2);
+}
+
+#[wasm_bindgen_test]
+async fn send_transaction_fails_for_transactions_that_fail_on_chain() {
+    let (mut adapter, client, transaction) = connected_signer("Failing Stub", true).await;
+    let signed = adapter.sign_transaction(transaction).await.unwrap();
+
+    assert_eq!(
+        adapter.send_transaction(client, signed).await,
+        Err(failed())
+    );
+}
+
+#[wasm_bindgen_test]
+async fn wallets_that_only_sign_need_a_connection_to_send_batches() {
+    let public_key = Pubkey::new_unique();
+    register_signer("Unconnected Stub", &public_key.to_string(), false);
+    let mut adapter: BaseWalletAdapter = Wallet::Standard("Unconnected Stub".to_string()).into();
+    assert!(adapter.connect().await.unwrap());
+    let mut transaction = Transaction::new_with_payer(&[], Some(&public_key));
+    transaction.message.recent_blockhash = Hash::new_unique();
+
+    assert_eq!(
+        adapter
+            .sign_send_all_transactions(vec![transaction], SendMode::Parallel)
+            .await,
+        Err(WalletError::WalletConfigError)
+    );
+    assert_eq!(send_count(), 0);
+}
+
+#[wasm_bindgen_test]
+async fn sequential_sends_go_through_when_every_transaction_succeeds() {
+    let (mut adapter, _, transaction) = connected_signer("Succeeding Stub", false).await;
+
+    let results = adapter
+        .sign_send_all_transactions(vec![transaction; 2], SendMode::Sequential)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        results,
+        vec![
+            Ok(Signature::from(SIGNATURE)),
+            Ok(Signature::from(SIGNATURE))
+        ]
+    );
+    assert_eq!(send_count(), 2);
+}
+
+fn sent_config() -> Value {
+    serde_json::from_str(&last_send_config()).unwrap()
+}
+
+fn options() -> SendOptions {
+    SendOptions {
+        skip_preflight: true,
+        preflight_commitment: Some(CommitmentLevel::Processed),
+        max_retries: Some(3),
+        min_context_slot: None,
+    }
+}
+
+#[wasm_bindgen_test]
+async fn signer_adapters_pass_send_options_on_to_the_rpc_node() {
+    let (adapter, _, transaction) = connected_signer("Options Stub", false).await;
+    let mut signer = StandardSignerWalletAdapter::try_from(adapter).unwrap();
+
+    signer
+        .sign_send_transaction_with_options(transaction.clone(), options())
+        .await
+        .unwrap();
+    assert_eq!(sent_config()["skipPreflight"], json!(true));
+    assert_eq!(sent_config()["preflightCommitment"], json!("processed"));
+    assert_eq!(sent_config()["maxRetries"], json!(3));
+
+    signer
+        .sign_send_all_transactions_with_options(
+            vec![transaction.clone()],
+            SendMode::Parallel,
+            options(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(sent_config()["maxRetries"], json!(3));
+
+    // The plain methods send with the default options.
+    signer.sign_send_transaction(transaction).await.unwrap();
+    assert_eq!(sent_config()["skipPreflight"], json!(false));
+    assert_eq!(sent_config()["preflightCommitment"], json!("confirmed"));
+    assert_eq!(sent_config()["maxRetries"], Value::Null);
+}
+
+#[wasm_bindgen_test]
+async fn wallet_adapters_pass_send_options_on_to_the_rpc_node() {
+    let (adapter, client, transaction) = connected_signer("Sending Stub", false).await;
+    let mut signer = StandardSignerWalletAdapter::try_from(adapter).unwrap();
+    let signed = signer.sign_transaction(transaction).await.unwrap();
+
+    signer
+        .send_transaction_with_options(client.clone(), signed.clone(), options())
+        .await
+        .unwrap();
+    assert_eq!(sent_config()["skipPreflight"], json!(true));
+    assert_eq!(sent_config()["maxRetries"], json!(3));
+
+    signer.send_transaction(client, signed).await.unwrap();
+    assert_eq!(sent_config()["skipPreflight"], json!(false));
+    assert_eq!(sent_config()["maxRetries"], Value::Null);
+}
@@ -0,0 +1,514 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+use futures::{
+    future::{select, Either},
+    StreamExt,
+};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+
+use wasi_sol::{
+    core::{
+        adapters::{
+            PhantomWalletAdapter, StandardMessageSignerWalletAdapter, StandardSignInWalletAdapter,
+            StandardSignerWalletAdapter, StandardWalletAdapter,
+        },
+        client::{Cluster, WasmClient},
+        error::WalletError,
+        events::{Subscription, WalletEvent},
+        standard::get_wallets,
+        traits::{
+            MessageSignerWalletAdapter, SignerWalletAdapter, WalletAdapter, WalletAdapterEvents,
+        },
+        wallet::{BaseWalletAdapter, Wallet, WalletReadyState},
+    },
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::Signature,
+    system_instruction,
+    transaction::Transaction,
+};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen(inline_js = r#"
+export function registerStubWallet(name, address, compatible) {
+    const account = {
+        address,
+        publicKey: new Uint8Array(32),
+        chains: ["solana:devnet"],
+        features: ["solana:signMessage"],
+    };
+    const features = {
+        "standard:connect": {
+            version: "1.0.0",
+            connect: async () => {
+                wallet.accounts = [account];
+                return { accounts: wallet.accounts };
+            },
+        },
+        "standard:disconnect": {
+            version: "1.0.0",
+            disconnect: async () => {
+                wallet.accounts = [];
+            },
+        },
+        "solana:signMessage": {
+            version: "1.0.0",
+            signMessage: async (...inputs) =>
+                inputs.map(({ message }) => ({
+                    signedMessage: message,
+                    signature: new Uint8Array(64).fill(7),
+                })),
+        },
+    };
+    if (compatible) {
+        features["standard:events"] = { version: "1.0.0", on: () => () => {} };
+        features["solana:signAndSendTransaction"] = {
+            version: "1.0.0",
+            supportedTransactionVersions: ["legacy", 0],
+            signAndSendTransaction: async (...inputs) =>
+                inputs.map(({ chain }) => {
+                    window.lastChain = chain;
+                    return { signature: new Uint8Array(64).fill(9) };
+                }),
+        };
+    }
+    const wallet = {
+        version: "1.0.0",
+        name,
+        icon: "data:image/svg+xml;base64,PHN2Zy8+",
+        chains: ["solana:devnet"],
+        features,
+        accounts: [],
+    };
+
+    const callback = ({ register }) => register(wallet);
+    try {
+        window.dispatchEvent(
+            new CustomEvent("wallet-standard:register-wallet", { detail: callback })
+        );
+    } catch (error) {
+        console.error(error);
+    }
+    window.addEventListener("wallet-standard:app-ready", ({ detail: api }) => callback(api));
+    return wallet;
+}
+
+// A wallet that signs its messages wrapped in a prefix and a suffix.
+export function registerWrappingWallet(name, address) {
+    const wallet = registerStubWallet(name, address, true);
+    wallet.features["solana:signMessage"].signMessage = async (...inputs) =>
+        inputs.map(({ message }) => ({
+            signedMessage: Uint8Array.of(1, ...message, 2),
+            signature: new Uint8Array(64).fill(7),
+        }));
+}
+
+// A wallet that only connects silently once the user approved the site in an interactive
+// connect.
+export function registerTrustingWallet(name, address) {
+    const wallet = registerStubWallet(name, address, true);
+    let trusted = false;
+    wallet.features["standard:connect"].connect = async (input) => {
+        window.connectInputs.push(input);
+        if (input && input.silent && !trusted) {
+            throw new Error("not trusted");
+        }
+        trusted = true;
+        wallet.accounts = [{
+            address,
+            publicKey: new Uint8Array(32),
+            chains: ["solana:devnet"],
+            features: ["solana:signMessage"],
+        }];
+        return { accounts: wallet.accounts };
+    };
+    window.connectInputs = [];
+}
+
+export function silentConnects() {
+    return window.connectInputs.map((input) => Boolean(input && input.silent));
+}
+
+// A wallet that can only sign transactions, filling the first signature with 7s, and an RPC
+// node that accepts whatever it is sent.
+export function registerSignOnlyWallet(name, address) {
+    const account = {
+        address,
+        publicKey: new Uint8Array(32),
+        chains: ["solana:devnet"],
+        features: ["solana:signTransaction"],
+    };
+    const wallet = {
+        version: "1.0.0",
+        name,
+        icon: "data:image/svg+xml;base64,PHN2Zy8+",
+        chains: ["solana:devnet"],
+        features: {
+            "standard:connect": {
+                version: "1.0.0",
+                connect: async () => {
+                    wallet.accounts = [account];
+                    return { accounts: wallet.accounts };
+                },
+            },
+            "standard:events": { version: "1.0.0", on: () => () => {} },
+            "solana:signTransaction": {
+                version: "1.0.0",
+                supportedTransactionVersions: ["legacy", 0],
+                signTransaction: async (...inputs) =>
+                    inputs.map(({ transaction }) => {
+                        const signedTransaction = transaction.slice();
+                        signedTransaction.fill(7, 1, 65);
+                        return { signedTransaction };
+                    }),
+            },
+        },
+        accounts: [],
+    };
+    const callback = ({ register }) => register(wallet);
+    window.dispatchEvent(new CustomEvent("wallet-standard:register-wallet", { detail: callback }));
+    window.addEventListener("wallet-standard:app-ready", ({ detail: api }) => callback(api));
+
+    globalThis.fetch = async (input, init) => {
+        const { id, method } = JSON.parse(init ? init.body : await input.text());
+        window.rpcMethods = [...(window.rpcMethods || []), method];
+        const result = method === "sendTransaction" ? window.expectedSignature : null;
+        const response = new Response(JSON.stringify({ jsonrpc: "2.0", id, result }), {
+            headers: { "content-type": "application/json" },
+        });
+        Object.defineProperty(response, "url", { value: "http://localhost:8899/" });
+        return response;
+    };
+}
+
+export function expectSignature(signature) {
+    window.expectedSignature = signature;
+}
+
+export function rpcMethods() {
+    return window.rpcMethods || [];
+}
+
+export function delay(ms) {
+    return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
+export function lastChain() {
+    return window.lastChain;
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = registerStubWallet)]
+    fn register_stub_wallet(name: &str, address: &str, compatible: bool);
+
+    #[wasm_bindgen(js_name = registerWrappingWallet)]
+    fn register_wrapping_wallet(name: &str, address: &str);
+
+    #[wasm_bindgen(js_name = registerTrustingWallet)]
+    fn register_trusting_wallet(name: &str, address: &str);
+
+    #[wasm_bindgen(js_name = silentConnects)]
+    fn silent_connects() -> Vec<JsValue>;
+
+    #[wasm_bindgen(js_name = registerSignOnlyWallet)]
+    fn register_sign_only_wallet(name: &str, address: &str);
+
+    #[wasm_bindgen(js_name = expectSignature)]
+    fn expect_signature(signature: &str);
+
+    #[wasm_bindgen(js_name = rpcMethods)]
+    fn rpc_methods() -> Vec<String>;
+
+    fn delay(ms: u32) -> js_sys::Promise;
+
+    #[wasm_bindgen(js_name = lastChain)]
+    fn last_chain() -> Option<String>;
+}
+
+/// The signatures of the `TransactionSent` events of `adapter`, for as long as the subscription
+/// is kept.
+fn sent_signatures(adapter: &BaseWalletAdapter) -> (Subscription, Rc<RefCell<Vec<Signature>>>) {
+    let sent = Rc::new(RefCell::new(Vec::new()));
+    let listener = sent.clone();
+    let subscription = adapter.subscribe(move |event| {
+        if let WalletEvent::TransactionSent(signature) = event {
+            listener.borrow_mut().push(*signature);
+        }
+    });
+    (subscription, sent)
+}
+
+fn find(name: &str) -> Option<BaseWalletAdapter> {
+    get_wallets()
+        .into_iter()
+        .find(|wallet| wallet.name() == name)
+}
+
+#[wasm_bindgen_test]
+fn leaves_wallets_with_a_builtin_adapter_out() {
+    register_stub_wallet("Backpack", &Pubkey::new_unique().to_string(), true);
+    register_stub_wallet("Backpack Stub", &Pubkey::new_unique().to_string(), true);
+
+    assert!(find("Backpack").is_none());
+    assert!(find("Backpack Stub").is_some());
+}
+
+#[wasm_bindgen_test]
+fn discovers_wallets_registered_before_and_after_app_ready() {
+    register_stub_wallet("Early Stub", &Pubkey::new_unique().to_string(), true);
+    let early = find("Early Stub").expect("wallet registered before app-ready");
+
+    assert_eq!(early.ready_state(), WalletReadyState::Installed);
+    assert!(early.icon().starts_with("data:image/svg+xml"));
+    assert!(early
+        .features()
+        .contains(&"solana:signAndSendTransaction".to_string()));
+
+    register_stub_wallet("Late Stub", &Pubkey::new_unique().to_string(), true);
+    assert!(find("Late Stub").is_some());
+}
+
+#[wasm_bindgen_test]
+fn ignores_wallets_without_solana_features() {
+    register_stub_wallet(
+        "Incompatible Stub",
+        &Pubkey::new_unique().to_string(),
+        false,
+    );
+
+    assert!(find("Incompatible Stub").is_none());
+}
+
+#[wasm_bindgen_test]
+async fn connects_and_signs_through_standard_features() {
+    let public_key = Pubkey::new_unique();
+    register_stub_wallet("Connect Stub", &public_key.to_string(), true);
+
+    let mut adapter: BaseWalletAdapter = Wallet::Standard("Connect Stub".to_string()).into();
+    assert!(adapter.connect().await.unwrap());
+    assert_eq!(adapter.public_key(), Some(public_key));
+
+    // The stub's signature is not a valid ed25519 signature for the account.
+    assert!(matches!(
+        adapter.sign_message("hello").await,
+        Err(WalletError::WalletInvalidSignatureError)
+    ));
+
+    adapter.set_skip_signature_verification(true);
+    let output = adapter.sign_message("hello").await.unwrap();
+    assert_eq!(output.signature.as_ref(), &[7u8; 64]);
+    assert_eq!(output.signed_message, b"hello");
+
+    assert!(adapter.disconnect().await.unwrap());
+    assert_eq!(adapter.public_key(), None);
+}
+
+#[wasm_bindgen_test]
+async fn auto_connect_only_reconnects_sites_the_user_approved() {
+    let public_key = Pubkey::new_unique();
+    register_trusting_wallet("Trusting Stub", &public_key.to_string());
+    let mut adapter: BaseWalletAdapter = Wallet::Standard("Trusting Stub".to_string()).into();
+    let errors = Rc::new(RefCell::new(Vec::new()));
+    let listener = errors.clone();
+    let _subscription = adapter.subscribe(move |event| {
+        if let WalletEvent::Error(err) = event {
+            listener.borrow_mut().push(err.clone());
+        }
+    });
+
+    // Not approved yet: no prompt, no error, just not connected.
+    assert_eq!(adapter.auto_connect().await, Ok(false));
+    assert_eq!(adapter.public_key(), None);
+    assert!(errors.borrow().is_empty());
+
+    assert_eq!(adapter.connect().await, Ok(true));
+    assert!(adapter.disconnect().await.unwrap());
+    assert_eq!(adapter.auto_connect().await, Ok(true));
+    assert_eq!(adapter.public_key(), Some(public_key));
+
+    let silent: Vec<_> = silent_connects()
+        .iter()
+        .map(|silent| silent.as_bool())
+        .collect();
+    assert_eq!(silent, vec![Some(true), Some(false), Some(true)]);
+}
+
+#[wasm_bindgen_test]
+async fn signing_messages_without_a_connection_emits_an_error() {
+    register_stub_wallet("Idle Stub", &Pubkey::new_unique().to_string(), true);
+    let mut adapter: BaseWalletAdapter = Wallet::Standard("Idle Stub".to_string()).into();
+    let errors = Rc::new(RefCell::new(Vec::new()));
+    let listener = errors.clone();
+    let _subscription = adapter.subscribe(move |event| {
+        if let WalletEvent::Error(err) = event {
+            listener.borrow_mut().push(err.clone());
+        }
+    });
+
+    assert_eq!(
+        adapter.sign_message("hello").await,
+        Err(WalletError::WalletNotConnectedError)
+    );
+    assert_eq!(*errors.borrow(), vec![WalletError::WalletNotConnectedError]);
+}
+
+#[wasm_bindgen_test]
+async fn rejects_messages_signed_inside_a_larger_payload() {
+    let public_key = Pubkey::new_unique();
+    register_wrapping_wallet("Wrapping Stub", &public_key.to_string());
+
+    let mut adapter: BaseWalletAdapter = Wallet::Standard("Wrapping Stub".to_string()).into();
+    assert!(adapter.connect().await.unwrap());
+    adapter.set_skip_signature_verification(true);
+
+    assert_eq!(
+        adapter.sign_message("hello").await,
+        Err(WalletError::WalletInvalidSignatureError)
+    );
+    assert_eq!(
+        adapter.sign_message("").await,
+        Err(WalletError::WalletSignMessageError)
+    );
+}
+
+#[wasm_bindgen_test]
+async fn signs_for_the_chain_of_the_cluster() {
+    let public_key = Pubkey::new_unique();
+    register_stub_wallet("Chain Stub", &public_key.to_string(), true);
+
+    let mut adapter: BaseWalletAdapter = Wallet::Standard("Chain Stub".to_string()).into();
+    assert!(adapter.connect().await.unwrap());
+    let transfer = system_instruction::transfer(&public_key, &Pubkey::new_unique(), 1);
+    let mut transaction = Transaction::new_with_payer(&[transfer], Some(&public_key));
+    transaction.message.recent_blockhash = Hash::new_unique();
+
+    // The stub's account only supports devnet, and adapters sign for mainnet by default.
+    assert_eq!(adapter.cluster(), Cluster::Mainnet);
+    assert_eq!(
+        adapter.sign_send_transaction(transaction.clone()).await,
+        Err(WalletError::WalletChainError {
+            chain: "solana:mainnet".to_string()
+        })
+    );
+    assert_eq!(last_chain(), None);
+
+    adapter.set_connection(Arc::new(WasmClient::new("https://api.devnet.solana.com")));
+    assert_eq!(adapter.cluster(), Cluster::Devnet);
+    let (_subscription, sent) = sent_signatures(&adapter);
+    assert_eq!(
+        adapter.sign_send_transaction(transaction.clone()).await,
+        Ok(Signature::from([9; 64]))
+    );
+    assert_eq!(*sent.borrow(), vec![Signature::from([9; 64])]);
+    assert_eq!(last_chain().as_deref(), Some("solana:devnet"));
+
+    adapter.set_cluster(Some(Cluster::Testnet));
+    assert!(matches!(
+        adapter.sign_send_transaction(transaction).await,
+        Err(WalletError::WalletChainError { .. })
+    ));
+}
+
+#[wasm_bindgen_test]
+fn derives_the_cluster_from_the_endpoint() {
+    for (endpoint, cluster) in [
+        ("https://api.mainnet-beta.solana.com", Cluster::Mainnet),
+        ("https://example-rpc.provider.com/key", Cluster::Mainnet),
+        ("https://api.devnet.solana.com", Cluster::Devnet),
+        ("https://DEVNET.helius-rpc.com/?api-key=x", Cluster::Devnet),
+        ("https://api.testnet.solana.com", Cluster::Testnet),
+        ("http://localhost:8899", Cluster::Localnet),
+        ("http://127.0.0.1:8899/", Cluster::Localnet),
+        ("http://[::1]:8899", Cluster::Localnet),
+        (
+            "https://devnet.example.com@mainnet.example.com",
+            Cluster::Mainnet,
+        ),
+    ] {
+        assert_eq!(Cluster::from_endpoint(endpoint), cluster, "{}", endpoint);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn standard_adapters_follow_the_declared_features() {
+    let public_key = Pubkey::new_unique();
+    // Signs messages and signs and sends transactions, but can't only sign them.
+    register_stub_wallet("Partial Stub", &public_key.to_string(), true);
+    let base: BaseWalletAdapter = Wallet::Standard("Partial Stub".to_string()).into();
+
+    assert!(StandardWalletAdapter::try_from(base.clone()).is_ok());
+    assert!(StandardSignerWalletAdapter::try_from(base.clone()).is_err());
+    assert!(StandardSignInWalletAdapter::try_from(base.clone()).is_err());
+    assert!(StandardWalletAdapter::try_from(BaseWalletAdapter::from(Wallet::Phantom)).is_err());
+    assert!(PhantomWalletAdapter::try_from(base.clone()).is_err());
+
+    let mut adapter = StandardMessageSignerWalletAdapter::try_from(base.clone()).unwrap();
+    assert!(adapter.connect().await.unwrap());
+    // Clones share the connection.
+    assert_eq!(base.public_key(), Some(public_key));
+
+    adapter.inner().set_skip_signature_verification(true);
+    let output = adapter.sign_message(b"hello").await.unwrap();
+    assert_eq!(output.signed_message, b"hello");
+}
+
+#[wasm_bindgen_test]
+async fn sends_through_the_connection_when_the_wallet_only_signs() {
+    let public_key = Pubkey::new_unique();
+    register_sign_only_wallet("Sign Only Stub", &public_key.to_string());
+    let signature = Signature::from([7; 64]);
+    expect_signature(&signature.to_string());
+
+    let base: BaseWalletAdapter = Wallet::Standard("Sign Only Stub".to_string()).into();
+    let mut adapter = StandardSignerWalletAdapter::try_from(base).unwrap();
+    assert!(adapter.connect().await.unwrap());
+    adapter.inner().set_skip_signature_verification(true);
+    adapter.inner().set_cluster(Some(Cluster::Devnet));
+
+    let transfer = system_instruction::transfer(&public_key, &Pubkey::new_unique(), 1);
+    let mut transaction = Transaction::new_with_payer(&[transfer], Some(&public_key));
+    transaction.message.recent_blockhash = Hash::new_unique();
+
+    // Without a connection there is nothing to send through.
+    assert_eq!(
+        adapter.sign_send_transaction(transaction.clone()).await,
+        Err(WalletError::WalletConfigError)
+    );
+
+    adapter
+        .inner()
+        .set_connection(Arc::new(WasmClient::new("http://localhost:8899")));
+    let (_subscription, sent) = sent_signatures(adapter.inner());
+    assert_eq!(
+        adapter.sign_send_transaction(transaction).await,
+        Ok(signature)
+    );
+    assert_eq!(*sent.borrow(), vec![signature]);
+    assert_eq!(rpc_methods(), vec!["sendTransaction"]);
+}
+
+#[wasm_bindgen_test]
+async fn subscribers_are_told_when_the_wallet_is_injected() {
+    let adapter: BaseWalletAdapter = Wallet::Standard("Injected Stub".to_string()).into();
+    assert_eq!(adapter.ready_state(), WalletReadyState::NotDetected);
+
+    let mut events = adapter.events();
+    register_stub_wallet("Injected Stub", &Pubkey::new_unique().to_string(), true);
+    let event = match select(events.next(), JsFuture::from(delay(3000))).await {
+        Either::Left((event, _)) => event,
+        Either::Right(_) => panic!("no ready state change"),
+    };
+    assert_eq!(
+        event,
+        Some(WalletEvent::ReadyStateChange(WalletReadyState::Installed))
+    );
+
+    // The watcher doesn't keep the adapter alive, so the stream ends with it.
+    drop(adapter);
+    assert_eq!(events.next().await, None);
+}